@@ -0,0 +1,527 @@
+extern crate nqueens_various;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process;
+
+use nqueens_various::{BoardRenderer, NQueens, ParseBoardError, RenderStyle, SvgRenderer};
+use nqueens_various::solvers::Solver;
+use nqueens_various::solvers::backtracking::{backtracking_for_each, Backtracking};
+use nqueens_various::solvers::bitmask::Bitmask;
+use nqueens_various::solvers::brute_force::BruteForce;
+use nqueens_various::solvers::fundamental::Fundamental;
+use nqueens_various::solvers::genetic::Genetic;
+use nqueens_various::solvers::hill_climbing::HillClimbing;
+use nqueens_various::solvers::min_conflicts::MinConflicts;
+use nqueens_various::solvers::simulated_annealing::SimulatedAnnealing;
+use nqueens_various::solvers::tabu_search::TabuSearch;
+
+const USAGE: &str = "\
+usage: nqueens <command> [options]
+
+commands:
+  solve [--solver SOLVER] [--seed SEED] [--max-restarts RESTARTS] [--format FORMAT] N
+      Print one solution for an N by N board. SOLVER is backtracking (the default),
+      brute-force, fundamental, genetic, hill-climbing, min-conflicts, simulated-annealing or
+      tabu-search.
+      Randomized solvers given the same SEED find the same solution; backtracking, brute-force
+      and fundamental don't take a seed. hill-climbing starts over from a new random board up
+      to RESTARTS (1000) times before giving up, and is the only solver to take --max-restarts.
+  count [--solver SOLVER] N
+      Print the number of solutions for an N by N board. SOLVER is bitmask (the default),
+      backtracking, brute-force or fundamental.
+  validate [FILE]
+      Check each board in FILE, or stdin, for conflicts. Exits with status 1 if any board is
+      not a solution.
+  render [--format FORMAT] [--coordinates] [--highlight] [--columns COLUMNS] [FILE]
+      Draw each board in FILE, or stdin. --highlight marks queens in conflict, or draws attack
+      lines in SVG. Several boards in SVG are drawn on one sheet, COLUMNS (8) boards wide.
+  enumerate [--format FORMAT] N
+      Print every solution for an N by N board as it is found.
+
+FORMAT is grid, unicode, list or svg (solve and render only). The default is grid, except for
+enumerate, which prints one list per line.
+
+Boards are read in any of the notations accepted by NQueens::from_str: a grid of Q and X or .,
+one row per line; a list like [1,3,0,2]; or digits like 1302. Boards drawn as grids are
+separated by blank lines.";
+
+/// Why a command didn't run to completion.
+enum CliError {
+    /// The command line was wrong, so the usage is printed after the message.
+    Usage(String),
+    Failed(String),
+    Io(io::Error),
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> CliError {
+        CliError::Io(e)
+    }
+}
+
+fn usage_error<T>(msg: String) -> Result<T, CliError> {
+    Err(CliError::Usage(msg))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // commands return Ok(false) if they ran but found something wrong, e.g. an invalid board
+    let code = match run(&args) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(CliError::Usage(msg)) => {
+            eprintln!("nqueens: {}\n\n{}", msg, USAGE);
+            2
+        }
+        Err(CliError::Failed(msg)) => {
+            eprintln!("nqueens: {}", msg);
+            1
+        }
+        // the reader of a pipeline stopped early, e.g. `nqueens enumerate 12 | head`
+        Err(CliError::Io(ref e)) if e.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(CliError::Io(e)) => {
+            eprintln!("nqueens: {}", e);
+            1
+        }
+    };
+    process::exit(code);
+}
+
+fn run(args: &[String]) -> Result<bool, CliError> {
+    let command = match args.first() {
+        Some(command) => command.as_str(),
+        None => return usage_error("no command given".to_string()),
+    };
+    let args = &args[1..];
+
+    match command {
+        "solve" => solve(&Options::parse(args, &["solver", "seed", "max-restarts", "format"], &[])?),
+        "count" => count(&Options::parse(args, &["solver"], &[])?),
+        "validate" => validate(&Options::parse(args, &[], &[])?),
+        "render" => render(&Options::parse(args, &["format", "columns"], &["coordinates", "highlight"])?),
+        "enumerate" => enumerate(&Options::parse(args, &["format"], &[])?),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        _ => usage_error(format!("unknown command {:?}", command)),
+    }
+}
+
+/// The options and positional arguments following a command.
+#[derive(Debug)]
+struct Options {
+    values: HashMap<String, String>,
+    flags: Vec<String>,
+    positional: Vec<String>,
+}
+
+impl Options {
+    /// Splits the arguments into `--name value` options for the names in `valued`, `--name`
+    /// flags for the names in `flags`, and positional arguments.
+    fn parse(args: &[String], valued: &[&str], flags: &[&str]) -> Result<Options, CliError> {
+        let mut options = Options {
+            values: HashMap::new(),
+            flags: Vec::new(),
+            positional: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                options.positional.push(arg.clone());
+                continue;
+            }
+
+            let name = &arg[2..];
+            if valued.contains(&name) {
+                match args.next() {
+                    Some(value) => options.values.insert(name.to_string(), value.clone()),
+                    None => return usage_error(format!("{} needs a value", arg)),
+                };
+            }
+            else if flags.contains(&name) {
+                options.flags.push(name.to_string());
+            }
+            else {
+                return usage_error(format!("unknown option {}", arg));
+            }
+        }
+        Ok(options)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|value| value.as_str())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    /// The board size, which is the only positional argument.
+    fn size(&self) -> Result<usize, CliError> {
+        match self.positional.as_slice() {
+            [size] => size.parse().or_else(|_| usage_error(format!("invalid board size {:?}", size))),
+            [] => usage_error("no board size given".to_string()),
+            _ => usage_error("too many arguments".to_string()),
+        }
+    }
+
+    /// The contents of the file named by the only positional argument, or stdin if there isn't
+    /// one.
+    fn input(&self) -> Result<String, CliError> {
+        let mut text = String::new();
+        match self.positional.as_slice() {
+            [] => {
+                io::stdin().read_to_string(&mut text)?;
+            }
+            [path] => {
+                let mut file = File::open(path)
+                    .map_err(|e| CliError::Failed(format!("{}: {}", path, e)))?;
+                file.read_to_string(&mut text)?;
+            }
+            _ => return usage_error("too many arguments".to_string()),
+        }
+        Ok(text)
+    }
+
+    fn format(&self, default: Format, allow_svg: bool) -> Result<Format, CliError> {
+        let format = match self.value("format") {
+            None => default,
+            Some("grid") => Format::Grid,
+            Some("unicode") => Format::Unicode,
+            Some("list") => Format::List,
+            Some("svg") if allow_svg => Format::Svg,
+            Some(other) => return usage_error(format!("unknown format {:?}", other)),
+        };
+        Ok(format)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Grid,
+    Unicode,
+    List,
+    Svg,
+}
+
+/// The number of restarts hill climbing is allowed if --max-restarts isn't given. A single climb on
+/// an 8 by 8 board only succeeds about one time in seven.
+const DEFAULT_MAX_RESTARTS: usize = 1000;
+
+/// Makes the solver with the given name. Giving a seed to a deterministic solver, or a number of
+/// restarts to anything but hill climbing, is an error rather than being silently ignored.
+fn solver(name: &str, seed: Option<u64>, max_restarts: Option<usize>) -> Result<Box<dyn Solver>, CliError> {
+    let solver: Box<dyn Solver> = match name {
+        "backtracking" => Box::new(Backtracking),
+        "bitmask" => Box::new(Bitmask),
+        "brute-force" => Box::new(BruteForce),
+        "fundamental" => Box::new(Fundamental),
+        "genetic" => Box::new(Genetic { seed, ..Genetic::default() }),
+        "hill-climbing" => Box::new(HillClimbing {
+            seed,
+            max_restarts: max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS),
+            ..HillClimbing::default()
+        }),
+        "min-conflicts" => Box::new(MinConflicts { seed, ..MinConflicts::default() }),
+        "simulated-annealing" => Box::new(SimulatedAnnealing { seed, ..SimulatedAnnealing::default() }),
+        "tabu-search" => Box::new(TabuSearch { seed, ..TabuSearch::default() }),
+        _ => return usage_error(format!("unknown solver {:?}", name)),
+    };
+    if seed.is_some() && ["backtracking", "bitmask", "brute-force", "fundamental"].contains(&name) {
+        return usage_error(format!("the {} solver doesn't take a seed", name));
+    }
+    if max_restarts.is_some() && name != "hill-climbing" {
+        return usage_error(format!("the {} solver doesn't take a number of restarts", name));
+    }
+    Ok(solver)
+}
+
+fn solve(options: &Options) -> Result<bool, CliError> {
+    let size = options.size()?;
+    let format = options.format(Format::Grid, true)?;
+    let seed = match options.value("seed") {
+        Some(seed) => Some(seed.parse().or_else(|_| usage_error(format!("invalid seed {:?}", seed)))?),
+        None => None,
+    };
+    let max_restarts = match options.value("max-restarts") {
+        Some(restarts) => Some(restarts.parse().or_else(|_| usage_error(format!("invalid number of restarts {:?}", restarts)))?),
+        None => None,
+    };
+    let solver = solver(options.value("solver").unwrap_or("backtracking"), seed, max_restarts)?;
+
+    let solution = solver.solve_one(size).map_err(|e| CliError::Failed(e.to_string()))?;
+    let mut out = io::stdout();
+    if format == Format::Svg {
+        write!(out, "{}", SvgRenderer::default().render(&solution))?;
+    }
+    else {
+        writeln!(out, "{}", text(&solution, format, BoardRenderer::default()))?;
+    }
+    Ok(true)
+}
+
+fn count(options: &Options) -> Result<bool, CliError> {
+    let size = options.size()?;
+    let solver = solver(options.value("solver").unwrap_or("bitmask"), None, None)?;
+
+    let count = solver.count_solutions(size).map_err(|e| CliError::Failed(e.to_string()))?;
+    writeln!(io::stdout(), "{}", count)?;
+    Ok(true)
+}
+
+fn validate(options: &Options) -> Result<bool, CliError> {
+    let text = options.input()?;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    let mut all_valid = true;
+    for (line, board) in read_boards(&text) {
+        match board {
+            Ok(q) => {
+                let empty = q.iter().filter(|row| row.is_none()).count();
+                let conflicts = q.count_conflicts();
+                if q.is_valid() {
+                    writeln!(out, "line {}: valid", line)?;
+                }
+                else if empty > 0 {
+                    writeln!(out, "line {}: invalid, {}, {}", line,
+                             plural(empty, "empty column"), plural(conflicts as usize, "conflict"))?;
+                }
+                else {
+                    writeln!(out, "line {}: invalid, {}", line, plural(conflicts as usize, "conflict"))?;
+                }
+                all_valid &= q.is_valid();
+            }
+            Err(e) => {
+                writeln!(out, "{}", e)?;
+                all_valid = false;
+            }
+        }
+    }
+    Ok(all_valid)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
+
+fn render(options: &Options) -> Result<bool, CliError> {
+    let format = options.format(Format::Grid, true)?;
+    let columns = match options.value("columns") {
+        Some(columns) => match columns.parse() {
+            Ok(columns) if columns > 0 => columns,
+            _ => return usage_error(format!("invalid number of columns {:?}", columns)),
+        },
+        None => 8,
+    };
+
+    let text_input = options.input()?;
+    let mut boards = Vec::new();
+    for (_, board) in read_boards(&text_input) {
+        boards.push(board.map_err(|e| CliError::Failed(e.to_string()))?);
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    if format == Format::Svg {
+        let svg = SvgRenderer { attack_lines: options.flag("highlight"), ..SvgRenderer::default() };
+        if boards.len() == 1 {
+            write!(out, "{}", svg.render(&boards[0]))?;
+        }
+        else {
+            write!(out, "{}", svg.render_sheet(&boards, columns))?;
+        }
+    }
+    else {
+        let renderer = BoardRenderer {
+            coordinates: options.flag("coordinates"),
+            highlight_conflicts: options.flag("highlight"),
+            ..BoardRenderer::default()
+        };
+        for (i, q) in boards.iter().enumerate() {
+            write_board(&mut out, i, q, format, renderer)?;
+        }
+    }
+    Ok(true)
+}
+
+fn enumerate(options: &Options) -> Result<bool, CliError> {
+    let size = options.size()?;
+    let format = options.format(Format::List, false)?;
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut written = 0;
+    let mut result = Ok(());
+    backtracking_for_each(size, |q| {
+        result = write_board(&mut out, written, q, format, BoardRenderer::default());
+        written += 1;
+        result.is_ok()
+    });
+    result?;
+    Ok(true)
+}
+
+/// Formats the board as text in the given format, using `renderer` for the options other than
+/// the style.
+fn text(q: &NQueens, format: Format, renderer: BoardRenderer) -> String {
+    match format {
+        Format::List => q.to_row_list(),
+        Format::Unicode => BoardRenderer { style: RenderStyle::Unicode, ..renderer }.render(q),
+        _ => renderer.render(q),
+    }
+}
+
+/// Writes the `index`th of a sequence of boards as text. Boards drawn as grids have a blank line
+/// between them, so that the output can be read back by `read_boards`.
+fn write_board<W: Write>(out: &mut W, index: usize, q: &NQueens, format: Format, renderer: BoardRenderer) -> io::Result<()> {
+    if index > 0 && format != Format::List {
+        writeln!(out)?;
+    }
+    writeln!(out, "{}", text(q, format, renderer))
+}
+
+/// Splits the input into boards, each along with the line it starts on. A board in list or digit
+/// notation is always on a line of its own, while consecutive lines of anything else are parsed
+/// together as a grid, up to the next blank line or single line board.
+fn read_boards(text: &str) -> Vec<(usize, Result<NQueens, ParseBoardError>)> {
+    let mut boards = Vec::new();
+    let mut grid: Vec<&str> = Vec::new();
+    let mut grid_start = 0;
+
+    for (i, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        let single_line = trimmed.starts_with('[') || (!trimmed.is_empty() &&
+            trimmed.chars().all(|c| c.is_ascii_digit() || c == '_' || c.is_whitespace()));
+
+        if trimmed.is_empty() || single_line {
+            push_grid(&mut boards, &mut grid, grid_start);
+        }
+
+        if single_line {
+            boards.push((i+1, parse_at(line, i+1)));
+        }
+        else if !trimmed.is_empty() {
+            if grid.is_empty() {
+                grid_start = i+1;
+            }
+            grid.push(line);
+        }
+    }
+    push_grid(&mut boards, &mut grid, grid_start);
+
+    boards
+}
+
+fn push_grid(boards: &mut Vec<(usize, Result<NQueens, ParseBoardError>)>, grid: &mut Vec<&str>, start: usize) {
+    if !grid.is_empty() {
+        boards.push((start, parse_at(&grid.join("\n"), start)));
+        grid.clear();
+    }
+}
+
+/// Parses a board which starts on the given line of the input, so that errors have the line
+/// number in the whole input.
+fn parse_at(text: &str, line: usize) -> Result<NQueens, ParseBoardError> {
+    text.parse().map_err(|mut e: ParseBoardError| {
+        e.line += line-1;
+        e
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_boards, solver, write_board, Format, Options};
+    use nqueens_various::BoardRenderer;
+    use nqueens_various::NQueens;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    pub fn test_options() {
+        let options = Options::parse(&args(&["--solver", "fundamental", "8", "--highlight"]), &["solver"], &["highlight"]).ok().unwrap();
+        assert!(options.value("solver") == Some("fundamental"));
+        assert!(options.flag("highlight"));
+        assert!(options.size().ok() == Some(8));
+
+        assert!(Options::parse(&args(&["--solver"]), &["solver"], &[]).is_err());
+        assert!(Options::parse(&args(&["--bogus"]), &["solver"], &[]).is_err());
+        assert!(Options::parse(&args(&["eight"]), &[], &[]).ok().unwrap().size().is_err());
+    }
+
+    #[test]
+    pub fn test_hill_climbing_restarts() {
+        // most single climbs on an 8 by 8 board end in a local minimum
+        for seed in 0..20 {
+            let hill_climbing = solver("hill-climbing", Some(seed), None).ok().unwrap();
+            assert!(hill_climbing.solve_one(8).is_ok_and(|q| q.is_valid()), "seed {}", seed);
+        }
+        let single_climb = solver("hill-climbing", Some(0), Some(0)).ok().unwrap();
+        assert!(single_climb.solve_one(8).is_err());
+    }
+
+    #[test]
+    pub fn test_solver_options() {
+        for name in &["backtracking", "bitmask", "brute-force", "fundamental"] {
+            assert!(solver(name, None, None).is_ok(), "{}", name);
+            assert!(solver(name, Some(0), None).is_err(), "{}", name);
+            assert!(solver(name, None, Some(10)).is_err(), "{}", name);
+        }
+        for name in &["genetic", "min-conflicts", "simulated-annealing", "tabu-search"] {
+            assert!(solver(name, Some(0), None).is_ok(), "{}", name);
+            assert!(solver(name, None, Some(10)).is_err(), "{}", name);
+        }
+        assert!(solver("hill-climbing", Some(0), Some(10)).is_ok());
+        assert!(solver("bogus", None, None).is_err());
+    }
+
+    #[test]
+    pub fn test_read_boards() {
+        let text = "[1,3,0,2]\n2031\n\nX Q X X\nX X X Q\nQ X X X\nX X Q X\n\n. Q\nQ .\n1302\n";
+        let boards = read_boards(text);
+        let lines: Vec<usize> = boards.iter().map(|b| b.0).collect();
+        assert!(lines == vec![1, 2, 4, 9, 11], "{:?}", lines);
+
+        let q = NQueens::from([2,0,3,1]);
+        assert!(boards[1].1 == Ok(q.clone()));
+        assert!(boards[2].1 == Ok(q));
+        assert!(boards[3].1 == Ok(NQueens::from([1,0])));
+    }
+
+    #[test]
+    pub fn test_read_errors() {
+        let boards = read_boards("[0]\n\nQ X\nX Y\n");
+        let e = boards[1].1.clone().unwrap_err();
+        assert!(boards[1].0 == 3);
+        assert!((e.line, e.column) == (4, 3), "{}", e);
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        let boards = vec![NQueens::from([1,3,0,2]), NQueens::from([0]), NQueens::from([2,0,3,1])];
+        let mut out = Vec::new();
+        for (i, q) in boards.iter().enumerate() {
+            write_board(&mut out, i, q, Format::Grid, BoardRenderer::default()).unwrap();
+        }
+        let text = String::from_utf8(out).unwrap();
+        let read: Vec<NQueens> = read_boards(&text).into_iter().map(|b| b.1.unwrap()).collect();
+        assert!(read == boards, "{}", text);
+
+        let mut out = Vec::new();
+        for (i, q) in boards.iter().enumerate() {
+            write_board(&mut out, i, q, Format::List, BoardRenderer::default()).unwrap();
+        }
+        let text = String::from_utf8(out).unwrap();
+        assert!(text == "[1,3,0,2]\n[0]\n[2,0,3,1]\n", "{}", text);
+        let read: Vec<NQueens> = read_boards(&text).into_iter().map(|b| b.1.unwrap()).collect();
+        assert!(read == boards, "{}", text);
+    }
+}
@@ -1,10 +1,30 @@
 extern crate rand;
 extern crate rayon;
 extern crate permutohedron;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
+mod nqueens_display;
+mod nqueens_error;
+mod nqueens_move;
+mod nqueens_parse;
+#[cfg(feature = "serde")]
+mod nqueens_serde;
 mod nqueens_struct;
 mod nqueens_successor;
+mod nqueens_svg;
+mod nqueens_symmetry;
+mod nqueens_tracked;
 pub mod solvers;
 
+pub use nqueens_display::*;
+pub use nqueens_error::*;
+pub use nqueens_move::*;
 pub use nqueens_struct::*;
 pub use nqueens_successor::*;
+pub use nqueens_svg::*;
+pub use nqueens_symmetry::*;
+pub use nqueens_tracked::*;
@@ -0,0 +1,188 @@
+use std::fmt;
+
+use super::{NQueens, NQueensTracked};
+
+/// The characters used to draw a board with a `BoardRenderer`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RenderStyle {
+    /// `Q` for queens and `.` for empty squares.
+    Ascii,
+    /// `♛` for queens on a checkered board of `□` and `■`.
+    Unicode,
+}
+
+/// Draws boards as text, one line per row with row 0 at the top, like the diagrams in the tests.
+///
+/// ```text
+/// . . Q .
+/// Q . . .
+/// . . . Q
+/// . Q . .
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BoardRenderer {
+    pub style: RenderStyle,
+    /// Label the columns along the top and the rows down the left with the indices used by
+    /// `NQueens::get` and `NQueens::set`.
+    pub coordinates: bool,
+    /// Draw queens which are in conflict with another queen as `*` (or `♕` in the Unicode style)
+    /// instead.
+    pub highlight_conflicts: bool,
+}
+
+impl Default for BoardRenderer {
+    fn default() -> BoardRenderer {
+        BoardRenderer {
+            style: RenderStyle::Ascii,
+            coordinates: false,
+            highlight_conflicts: false,
+        }
+    }
+}
+
+impl BoardRenderer {
+    /// A renderer with the given style, no coordinates and no highlighting
+    pub fn new(style: RenderStyle) -> BoardRenderer {
+        BoardRenderer {
+            style,
+            ..BoardRenderer::default()
+        }
+    }
+
+    /// Draws the board. Lines are separated by newlines, with no newline at the end.
+    pub fn render(&self, board: &NQueens) -> String {
+        let size = board.size();
+
+        let conflicted: Vec<bool> = if self.highlight_conflicts {
+            let tracked = NQueensTracked::new(board.clone());
+            (0..size).map(|column| tracked.is_attacked(column)).collect()
+        }
+        else {
+            vec![false; size]
+        };
+
+        // every cell is as wide as the widest label so that the columns line up
+        let width = if self.coordinates { size.saturating_sub(1).to_string().len() } else { 1 };
+
+        let mut lines = Vec::new();
+        if self.coordinates {
+            let mut cells = vec![" ".repeat(width)];
+            cells.extend((0..size).map(|column| format!("{:<w$}", column, w = width)));
+            lines.push(cells.join(" ").trim_end().to_string());
+        }
+
+        for row in 0..size {
+            let mut cells = Vec::new();
+            if self.coordinates {
+                cells.push(format!("{:>w$}", row, w = width));
+            }
+            for (column, &in_conflict) in conflicted.iter().enumerate() {
+                let queen = board.get_option(column) == Some(row);
+                let square = self.square(queen, in_conflict, (column+row) % 2 == 1);
+                cells.push(format!("{:<w$}", square, w = width));
+            }
+            lines.push(cells.join(" ").trim_end().to_string());
+        }
+
+        lines.join("\n")
+    }
+
+    fn square(&self, queen: bool, conflicted: bool, dark: bool) -> char {
+        match (self.style, queen, conflicted, dark) {
+            (RenderStyle::Ascii, true, false, _) => 'Q',
+            (RenderStyle::Ascii, true, true, _) => '*',
+            (RenderStyle::Ascii, false, _, _) => '.',
+            (RenderStyle::Unicode, true, false, _) => '♛',
+            (RenderStyle::Unicode, true, true, _) => '♕',
+            (RenderStyle::Unicode, false, _, false) => '□',
+            (RenderStyle::Unicode, false, _, true) => '■',
+        }
+    }
+}
+
+impl NQueens {
+    /// Formats the board in the `[3,5,7,1,6,0,2,4]` notation accepted by `NQueens::from_str`, with
+    /// `_` for an empty column.
+    pub fn to_row_list(&self) -> String {
+        let rows: Vec<String> = self.iter()
+            .map(|row| row.map_or("_".to_string(), |row| row.to_string()))
+            .collect();
+        format!("[{}]", rows.join(","))
+    }
+}
+
+/// Draws the board with the default `BoardRenderer`, i.e. in ASCII without coordinates.
+impl fmt::Display for NQueens {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", BoardRenderer::default().render(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BoardRenderer, RenderStyle};
+    use NQueens;
+
+    #[test]
+    pub fn test_display() {
+        let q = NQueens::from([1,3,0,2]);
+        assert!(q.to_string() == ". . Q .\nQ . . .\n. . . Q\n. Q . .", "{}", q);
+    }
+
+    #[test]
+    pub fn test_display_empty() {
+        assert!(NQueens::new_empty(0).to_string() == "");
+
+        let mut q = NQueens::new_empty(3);
+        q.set(1, 2);
+        assert!(q.to_string() == ". . .\n. . .\n. Q .", "{}", q);
+    }
+
+    #[test]
+    pub fn test_row_list() {
+        let mut q = NQueens::from([1,3,0,2]);
+        assert!(q.to_row_list() == "[1,3,0,2]");
+        q.unset(1);
+        assert!(q.to_row_list() == "[1,_,0,2]");
+        assert!(q.to_row_list().parse::<NQueens>().unwrap() == q);
+        assert!(NQueens::new_empty(0).to_row_list() == "[]");
+    }
+
+    #[test]
+    pub fn test_unicode() {
+        let q = NQueens::from([1,3,0,2]);
+        let rendered = BoardRenderer::new(RenderStyle::Unicode).render(&q);
+        assert!(rendered == "□ ■ ♛ ■\n♛ □ ■ □\n□ ■ □ ♛\n■ ♛ ■ □", "{}", rendered);
+    }
+
+    #[test]
+    pub fn test_coordinates() {
+        let q = NQueens::from([1,3,0,2]);
+        let renderer = BoardRenderer { coordinates: true, ..BoardRenderer::default() };
+        let rendered = renderer.render(&q);
+        assert!(rendered == "  0 1 2 3\n0 . . Q .\n1 Q . . .\n2 . . . Q\n3 . Q . .", "{}", rendered);
+
+        // labels wider than one character widen every cell
+        let q = NQueens::new_empty(11);
+        let rendered = renderer.render(&q);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert!(lines[0] == "   0  1  2  3  4  5  6  7  8  9  10", "{}", lines[0]);
+        assert!(lines[1] == " 0 .  .  .  .  .  .  .  .  .  .  .", "{}", lines[1]);
+        assert!(lines[11] == "10 .  .  .  .  .  .  .  .  .  .  .", "{}", lines[11]);
+    }
+
+    #[test]
+    pub fn test_highlight_conflicts() {
+        let q = NQueens::from([0,2,1]);
+        // Q X X
+        // X X Q
+        // X Q X
+        let renderer = BoardRenderer { highlight_conflicts: true, ..BoardRenderer::default() };
+        let rendered = renderer.render(&q);
+        assert!(rendered == "Q . .\n. . *\n. * .", "{}", rendered);
+
+        let renderer = BoardRenderer { highlight_conflicts: true, ..BoardRenderer::new(RenderStyle::Unicode) };
+        let rendered = renderer.render(&q);
+        assert!(rendered == "♛ ■ □\n■ □ ♕\n□ ♕ □", "{}", rendered);
+    }
+}
@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fmt;
+
+/// Errors returned by the fallible (`try_`) accessors and constructors of `NQueens`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BoardError {
+    /// A queen's row is not on a board of the given size.
+    RowOutOfRange { column: usize, row: usize, size: usize },
+    /// The column is not on a board of the given size.
+    ColumnOutOfBounds { column: usize, size: usize },
+    /// There is no queen in the column.
+    EmptyColumn { column: usize },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BoardError::RowOutOfRange { column, row, size } => {
+                write!(f, "row {} in column {} is out of range for a board of size {}", row, column, size)
+            }
+            BoardError::ColumnOutOfBounds { column, size } => {
+                write!(f, "column {} is out of bounds for a board of size {}", column, size)
+            }
+            BoardError::EmptyColumn { column } => {
+                write!(f, "there is no queen in column {}", column)
+            }
+        }
+    }
+}
+
+impl Error for BoardError {}
+
+/// The error returned when parsing an `NQueens` from a string fails. `line` and `column` are the
+/// 1-based position in the input text, counted in characters, where the problem was found.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParseBoardError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseBoardErrorKind,
+}
+
+/// The ways in which parsing an `NQueens` from a string can fail.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParseBoardErrorKind {
+    /// A character which isn't allowed at this point in the input.
+    UnexpectedCharacter { found: char },
+    /// The input ended before the board was complete, e.g. a list without a closing `]`.
+    UnexpectedEnd,
+    /// A number in a list is too large to be a row on any board.
+    InvalidNumber,
+    /// A queen's row is not on a board of the given size.
+    RowOutOfRange { row: usize, size: usize },
+    /// A row of a grid has a different number of squares than the first row.
+    WrongRowLength { expected: usize, found: usize },
+    /// A grid has a different number of rows than it has squares in each row.
+    WrongRowCount { expected: usize, found: usize },
+    /// A column of a grid has a second queen in it.
+    TwoQueensInColumn { column: usize },
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl fmt::Display for ParseBoardErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseBoardErrorKind::UnexpectedCharacter { found } => {
+                write!(f, "unexpected character {:?}", found)
+            }
+            ParseBoardErrorKind::UnexpectedEnd => {
+                write!(f, "unexpected end of input")
+            }
+            ParseBoardErrorKind::InvalidNumber => {
+                write!(f, "number is too large")
+            }
+            ParseBoardErrorKind::RowOutOfRange { row, size } => {
+                write!(f, "row {} is out of range for a board of size {}", row, size)
+            }
+            ParseBoardErrorKind::WrongRowLength { expected, found } => {
+                write!(f, "expected {} squares in the row, found {}", expected, found)
+            }
+            ParseBoardErrorKind::WrongRowCount { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+            ParseBoardErrorKind::TwoQueensInColumn { column } => {
+                write!(f, "there is already a queen in column {}", column)
+            }
+        }
+    }
+}
+
+impl Error for ParseBoardError {}
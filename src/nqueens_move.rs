@@ -0,0 +1,194 @@
+use super::{NQueens, NQueensTracked};
+
+/// A description of a successor from `NQueens::successors_iter`: the queen in `column` moved from
+/// row `from` to row `to`. Moves are small and `Copy`, so a solver can score every successor of a
+/// board with `NQueensTracked::move_delta` and only build the board it picks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Move {
+    pub column: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Move {
+    /// Returns the move which takes the queen back to where it came from.
+    pub fn reversed(&self) -> Move {
+        Move {
+            column: self.column,
+            from: self.to,
+            to: self.from,
+        }
+    }
+}
+
+/// An iterator over the moves which turn a board into each of its successors, in the same order as
+/// `NQueensSuccessorIter` returns the successors. It borrows the board and doesn't allocate.
+pub struct NQueensMoveIter<'original> {
+    original: &'original NQueens,
+    column: usize,
+    row: usize,
+}
+
+impl<'original> NQueensMoveIter<'original> {
+    pub fn new(original: &NQueens) -> NQueensMoveIter<'_> {
+        NQueensMoveIter {
+            original,
+            column: 0,
+            row: 0,
+        }
+    }
+}
+
+impl<'original> Iterator for NQueensMoveIter<'original> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        let size = self.original.size();
+        while self.column < size {
+            let from = match self.original.get_option(self.column) {
+                Some(from) if self.row < size => from,
+                // an empty column, or the end of the current one
+                _ => {
+                    self.column += 1;
+                    self.row = 0;
+                    continue;
+                }
+            };
+
+            let to = self.row;
+            self.row += 1;
+            if to != from {
+                return Some(Move { column: self.column, from, to });
+            }
+        }
+        None
+    }
+}
+
+impl NQueens {
+    /// Creates a struct that implements `Iterator` which provides the moves leading to each board
+    /// that `successors_iter` would return, in the same order, without cloning the board.
+    pub fn moves_iter(&self) -> NQueensMoveIter<'_> {
+        NQueensMoveIter::new(self)
+    }
+
+    /// Makes the move. Panics if the queen in the move's column isn't in the row it moves from.
+    pub fn apply_move(&mut self, m: Move) {
+        assert!(self.get_option(m.column) == Some(m.from), "{:?} doesn't start from column {} of {:?}", m, m.column, self);
+        self.set(m.column, m.to);
+    }
+
+    /// Takes back the move, which must be the last one made in its column. Panics if the queen in
+    /// the move's column isn't in the row it moved to.
+    pub fn undo_move(&mut self, m: Move) {
+        self.apply_move(m.reversed());
+    }
+
+    /// Returns how many more conflicts the board would have after the move, in O(n) and without
+    /// changing the board. This is negative if the move removes conflicts.
+    pub fn move_delta(&self, m: Move) -> i64 {
+        let attackers = |row: usize| {
+            self.iter().enumerate()
+                .filter(|&(column, _)| column != m.column)
+                .filter(|&(column, other)| match *other {
+                    Some(other) => other == row || column+other == m.column+row || column+row == m.column+other,
+                    None => false,
+                })
+                .count() as i64
+        };
+        attackers(m.to) - attackers(m.from)
+    }
+}
+
+impl NQueensTracked {
+    /// Makes the move, updating the counts in O(1). Panics if the queen in the move's column isn't
+    /// in the row it moves from.
+    pub fn apply_move(&mut self, m: Move) {
+        assert!(self.get_option(m.column) == Some(m.from), "{:?} doesn't start from column {} of {:?}", m, m.column, self.board());
+        self.set(m.column, m.to);
+    }
+
+    /// Takes back the move, updating the counts in O(1).
+    pub fn undo_move(&mut self, m: Move) {
+        self.apply_move(m.reversed());
+    }
+
+    /// Returns how many more conflicts the board would have after the move, in O(1) and without
+    /// changing the board. This is negative if the move removes conflicts.
+    pub fn move_delta(&self, m: Move) -> i64 {
+        self.attackers(m.column, m.to) as i64 - self.attackers(m.column, m.from) as i64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Move;
+    use {NQueens, NQueensTracked};
+    use solvers::seeded_rng;
+
+    #[test]
+    pub fn test_moves_match_successors() {
+        let mut rng = seeded_rng(0);
+        for size in 0..10 {
+            let mut boards = vec![NQueens::new_empty(size), NQueens::new_random_with_rng(size, &mut rng)];
+            let mut partial = NQueens::new_random_permutation_with_rng(size, &mut rng);
+            for column in (0..size).filter(|column| column % 3 == 1) {
+                partial.unset(column);
+            }
+            boards.push(partial);
+
+            for q in boards {
+                let successors: Vec<NQueens> = q.successors_iter().collect();
+                let moved: Vec<NQueens> = q.moves_iter()
+                    .map(|m| {
+                        let mut successor = q.clone();
+                        successor.apply_move(m);
+                        successor
+                    })
+                    .collect();
+                assert!(successors == moved, "{:?}", q);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_apply_undo() {
+        let mut q = NQueens::from([1,3,0,2]);
+        let m = Move { column: 2, from: 0, to: 3 };
+        q.apply_move(m);
+        assert!(q == NQueens::from([1,3,3,2]));
+        q.undo_move(m);
+        assert!(q == NQueens::from([1,3,0,2]));
+
+        let mut tracked = NQueensTracked::new(q.clone());
+        tracked.apply_move(m);
+        assert!(tracked.count_conflicts() == NQueens::from([1,3,3,2]).count_conflicts());
+        tracked.undo_move(m);
+        assert!(tracked.count_conflicts() == 0 && tracked.board() == &q);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_apply_wrong_move() {
+        let mut q = NQueens::from([1,3,0,2]);
+        q.apply_move(Move { column: 2, from: 1, to: 3 });
+    }
+
+    #[test]
+    pub fn test_move_delta() {
+        let mut rng = seeded_rng(1);
+        for size in 1..10 {
+            let q = NQueens::new_random_with_rng(size, &mut rng);
+            let tracked = NQueensTracked::new(q.clone());
+            let conflicts = q.count_conflicts() as i64;
+            for m in q.moves_iter() {
+                let mut successor = q.clone();
+                successor.apply_move(m);
+                let expected = successor.count_conflicts() as i64 - conflicts;
+                assert!(q.move_delta(m) == expected, "{:?} on {:?}", m, q);
+                assert!(tracked.move_delta(m) == expected, "{:?} on {:?}", m, q);
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::str::FromStr;
+
+use super::{NQueens, ParseBoardError, ParseBoardErrorKind};
+
+/// A character of the input along with its 1-based line and column.
+#[derive(Debug, Clone, Copy)]
+struct Positioned {
+    line: usize,
+    column: usize,
+    ch: char,
+}
+
+impl Positioned {
+    fn error(&self, kind: ParseBoardErrorKind) -> ParseBoardError {
+        ParseBoardError { line: self.line, column: self.column, kind }
+    }
+
+    /// An error pointing just past this character, for when the input ends too early.
+    fn error_after(&self, kind: ParseBoardErrorKind) -> ParseBoardError {
+        ParseBoardError { line: self.line, column: self.column+1, kind }
+    }
+}
+
+/// Parses a board from any of three notations:
+///
+/// - A grid with row 0 on the first line, as used in the comments throughout the tests and as
+///   produced by `Display` and `BoardRenderer` (without coordinates). Queens are `Q`, `*`, `♛` or
+///   `♕` and empty squares are `X`, `.`, `□` or `■`. Whitespace between squares, indentation and
+///   blank lines are ignored, so `QXX` and `Q X X` are the same row.
+/// - A list of rows in column order, e.g. `[3,5,7,1,6,0,2,4]`, with `_` for an empty column.
+/// - A string of single digits in column order on one line, e.g. `35716024`, again with `_` for
+///   an empty column. Whitespace is ignored, so this only works for boards of size at most 10.
+///
+/// An input with nothing but whitespace in it is the board of size 0.
+///
+/// ```
+/// use nqueens_various::NQueens;
+///
+/// let q: NQueens = "X Q X X
+///                   X X X Q
+///                   Q X X X
+///                   X X Q X".parse().unwrap();
+/// assert!(q == NQueens::from([2,0,3,1]));
+/// assert!(q == "[2,0,3,1]".parse().unwrap());
+/// assert!(q == "2031".parse().unwrap());
+/// ```
+impl FromStr for NQueens {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<NQueens, ParseBoardError> {
+        let chars: Vec<Positioned> = positioned(s).into_iter()
+            .filter(|p| !p.ch.is_whitespace())
+            .collect();
+
+        let first = match chars.first() {
+            Some(first) => *first,
+            None => return Ok(NQueens::new_empty(0)),
+        };
+
+        let one_line = chars.iter().all(|p| p.line == first.line);
+        if first.ch == '[' {
+            parse_list(s)
+        }
+        else if one_line && chars.iter().all(|p| p.ch.is_ascii_digit() || p.ch == '_') {
+            parse_digits(&chars)
+        }
+        else {
+            parse_grid(&chars)
+        }
+    }
+}
+
+/// Splits the input into characters labelled with their positions.
+fn positioned(s: &str) -> Vec<Positioned> {
+    s.lines().enumerate()
+        .flat_map(|(i, line)| line.chars().enumerate().map(move |(j, ch)| {
+            Positioned { line: i+1, column: j+1, ch }
+        }))
+        .collect()
+}
+
+/// Parses the `[3,5,7,1,6,0,2,4]` notation. This works on the raw input rather than with the
+/// whitespace filtered out, so that `[1 2]` is an error rather than `[12]`.
+fn parse_list(s: &str) -> Result<NQueens, ParseBoardError> {
+    let chars = positioned(s);
+    let last = *chars.iter().rev().find(|p| !p.ch.is_whitespace()).unwrap();
+
+    let mut i = 0;
+    let skip_whitespace = |i: &mut usize| {
+        while *i < chars.len() && chars[*i].ch.is_whitespace() {
+            *i += 1;
+        }
+    };
+    let unexpected = |p: &Positioned| p.error(ParseBoardErrorKind::UnexpectedCharacter { found: p.ch });
+    let end = || last.error_after(ParseBoardErrorKind::UnexpectedEnd);
+
+    // the caller has already checked that the first non-whitespace character is the `[`
+    skip_whitespace(&mut i);
+    i += 1;
+
+    // each entry is a row, or None for an empty column, along with where it was in the input
+    let mut entries: Vec<(Option<usize>, Positioned)> = Vec::new();
+    skip_whitespace(&mut i);
+    if i < chars.len() && chars[i].ch == ']' {
+        i += 1;
+    }
+    else {
+        loop {
+            skip_whitespace(&mut i);
+            let start = *chars.get(i).ok_or_else(end)?;
+            if start.ch == '_' {
+                entries.push((None, start));
+                i += 1;
+            }
+            else if let Some(digit) = start.ch.to_digit(10) {
+                let mut row = digit as usize;
+                i += 1;
+                while let Some(digit) = chars.get(i).and_then(|p| p.ch.to_digit(10)) {
+                    row = row.checked_mul(10)
+                        .and_then(|row| row.checked_add(digit as usize))
+                        .ok_or_else(|| start.error(ParseBoardErrorKind::InvalidNumber))?;
+                    i += 1;
+                }
+                entries.push((Some(row), start));
+            }
+            else {
+                return Err(unexpected(&start));
+            }
+
+            skip_whitespace(&mut i);
+            let separator = *chars.get(i).ok_or_else(end)?;
+            i += 1;
+            match separator.ch {
+                ',' => continue,
+                ']' => break,
+                _ => return Err(unexpected(&separator)),
+            }
+        }
+    }
+
+    skip_whitespace(&mut i);
+    if let Some(trailing) = chars.get(i) {
+        return Err(unexpected(trailing));
+    }
+
+    let size = entries.len();
+    let mut q = NQueens::new_empty(size);
+    for (column, &(row, position)) in entries.iter().enumerate() {
+        if let Some(row) = row {
+            if row >= size {
+                return Err(position.error(ParseBoardErrorKind::RowOutOfRange { row, size }));
+            }
+        }
+        q.set_option(column, row);
+    }
+    Ok(q)
+}
+
+/// Parses the `35716024` notation, given the non-whitespace characters of the input.
+fn parse_digits(chars: &[Positioned]) -> Result<NQueens, ParseBoardError> {
+    let size = chars.len();
+    let mut q = NQueens::new_empty(size);
+    for (column, p) in chars.iter().enumerate() {
+        if let Some(row) = p.ch.to_digit(10) {
+            let row = row as usize;
+            if row >= size {
+                return Err(p.error(ParseBoardErrorKind::RowOutOfRange { row, size }));
+            }
+            q.set(column, row);
+        }
+    }
+    Ok(q)
+}
+
+/// Parses the grid notation, given the non-whitespace characters of the input.
+fn parse_grid(chars: &[Positioned]) -> Result<NQueens, ParseBoardError> {
+    let mut lines: Vec<&[Positioned]> = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len()+1 {
+        if i == chars.len() || chars[i].line != chars[start].line {
+            lines.push(&chars[start..i]);
+            start = i;
+        }
+    }
+
+    let size = lines[0].len();
+    let mut q = NQueens::new_empty(size);
+    for (row, squares) in lines.iter().enumerate() {
+        if row == size {
+            let found = lines.len();
+            return Err(squares[0].error(ParseBoardErrorKind::WrongRowCount { expected: size, found }));
+        }
+        if squares.len() != size {
+            let found = squares.len();
+            return Err(squares[0].error(ParseBoardErrorKind::WrongRowLength { expected: size, found }));
+        }
+
+        for (column, p) in squares.iter().enumerate() {
+            match p.ch {
+                'Q' | '*' | '♛' | '♕' => {
+                    if q.is_set(column) {
+                        return Err(p.error(ParseBoardErrorKind::TwoQueensInColumn { column }));
+                    }
+                    q.set(column, row);
+                }
+                'X' | '.' | '□' | '■' => {}
+                found => return Err(p.error(ParseBoardErrorKind::UnexpectedCharacter { found })),
+            }
+        }
+    }
+
+    if lines.len() < size {
+        let last = lines[lines.len()-1];
+        let found = lines.len();
+        return Err(last[last.len()-1].error_after(ParseBoardErrorKind::WrongRowCount { expected: size, found }));
+    }
+    Ok(q)
+}
+
+#[cfg(test)]
+mod test {
+    use {BoardRenderer, NQueens, ParseBoardError, ParseBoardErrorKind, RenderStyle};
+    use solvers::brute_force::brute_force_solutions;
+
+    fn parse_error(s: &str) -> (usize, usize, ParseBoardErrorKind) {
+        let e: ParseBoardError = s.parse::<NQueens>().unwrap_err();
+        (e.line, e.column, e.kind)
+    }
+
+    #[test]
+    pub fn test_parse_grid() {
+        let q: NQueens = "
+            X Q X X X
+            X X X Q X
+            Q X X X X
+            X X Q X X
+            X X X X Q
+        ".parse().unwrap();
+        assert!(q == NQueens::from([2,0,3,1,4]), "{:?}", q);
+
+        let q: NQueens = "Q..\n...\n.Q.".parse().unwrap();
+        assert!(q.get(0) == 0 && q.get(1) == 2 && !q.is_set(2));
+    }
+
+    #[test]
+    pub fn test_parse_list() {
+        let q: NQueens = "[3,5,7,1,6,0,2,4]".parse().unwrap();
+        assert!(q == NQueens::from([3,5,7,1,6,0,2,4]));
+
+        let q: NQueens = " [ 1, _,\n 0 ] ".parse().unwrap();
+        assert!(q.get(0) == 1 && !q.is_set(1) && q.get(2) == 0);
+
+        let q: NQueens = "[]".parse().unwrap();
+        assert!(q.size() == 0);
+
+        let q: NQueens = "[10,0,0,0,0,0,0,0,0,0,0]".parse().unwrap();
+        assert!(q.get(0) == 10);
+    }
+
+    #[test]
+    pub fn test_parse_digits() {
+        let q: NQueens = "35716024".parse().unwrap();
+        assert!(q == NQueens::from([3,5,7,1,6,0,2,4]));
+
+        let q: NQueens = "3571602 4".parse().unwrap();
+        assert!(q == NQueens::from([3,5,7,1,6,0,2,4]));
+
+        let q: NQueens = "1_0".parse().unwrap();
+        assert!(q.get(0) == 1 && !q.is_set(1) && q.get(2) == 0);
+
+        let q: NQueens = "".parse().unwrap();
+        assert!(q.size() == 0);
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        for q in brute_force_solutions(6) {
+            assert!(q.to_string().parse::<NQueens>().unwrap() == q, "{}", q);
+        }
+
+        let mut q = NQueens::from([0,2,1,1]);
+        q.unset(3);
+        assert!(q.to_string().parse::<NQueens>().unwrap() == q, "{}", q);
+
+        let renderer = BoardRenderer { highlight_conflicts: true, ..BoardRenderer::new(RenderStyle::Unicode) };
+        assert!(renderer.render(&q).parse::<NQueens>().unwrap() == q);
+    }
+
+    #[test]
+    pub fn test_grid_errors() {
+        assert!(parse_error("Q X\nX Y") == (2, 3, ParseBoardErrorKind::UnexpectedCharacter { found: 'Y' }));
+        assert!(parse_error("Q X\nX") == (2, 1, ParseBoardErrorKind::WrongRowLength { expected: 2, found: 1 }));
+        assert!(parse_error("Q X\nX Q\nX X") == (3, 1, ParseBoardErrorKind::WrongRowCount { expected: 2, found: 3 }));
+        assert!(parse_error("Q X X\nX X Q") == (2, 6, ParseBoardErrorKind::WrongRowCount { expected: 3, found: 2 }));
+        assert!(parse_error("Q X\nQ X") == (2, 1, ParseBoardErrorKind::TwoQueensInColumn { column: 0 }));
+    }
+
+    #[test]
+    pub fn test_list_errors() {
+        assert!(parse_error("[0,1") == (1, 5, ParseBoardErrorKind::UnexpectedEnd));
+        assert!(parse_error("[0,]") == (1, 4, ParseBoardErrorKind::UnexpectedCharacter { found: ']' }));
+        assert!(parse_error("[0 1]") == (1, 4, ParseBoardErrorKind::UnexpectedCharacter { found: '1' }));
+        assert!(parse_error("[0,1] 2") == (1, 7, ParseBoardErrorKind::UnexpectedCharacter { found: '2' }));
+        assert!(parse_error("[0,\n 2]") == (2, 2, ParseBoardErrorKind::RowOutOfRange { row: 2, size: 2 }));
+        assert!(parse_error("[99999999999999999999999]") == (1, 2, ParseBoardErrorKind::InvalidNumber));
+    }
+
+    #[test]
+    pub fn test_digit_errors() {
+        assert!(parse_error("0 1 3") == (1, 5, ParseBoardErrorKind::RowOutOfRange { row: 3, size: 3 }));
+
+        let e = "013".parse::<NQueens>().unwrap_err();
+        assert!(e.to_string() == "line 1, column 3: row 3 is out of range for a board of size 3", "{}", e);
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+
+use super::NQueens;
+
+/// The serialized form of an `NQueens`. In JSON this is `{"size":4,"queens":[1,3,null,2]}`, with
+/// `null` for an empty column. The size is redundant with the length of `queens`, but saves readers
+/// in other languages from having to work it out, and is checked when deserializing.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "NQueens")]
+struct NQueensRepr {
+    size: usize,
+    queens: Vec<Option<usize>>,
+}
+
+impl Serialize for NQueens {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let repr = NQueensRepr {
+            size: self.size(),
+            queens: self.iter().cloned().collect(),
+        };
+        repr.serialize(serializer)
+    }
+}
+
+/// Fails if the length of `queens` isn't `size` or if a queen is outside of the board, rather
+/// than panicking as `From` does.
+impl<'de> Deserialize<'de> for NQueens {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<NQueens, D::Error> {
+        let repr = NQueensRepr::deserialize(deserializer)?;
+        if repr.queens.len() != repr.size {
+            return Err(D::Error::custom(format_args!("expected {} queens for a board of size {}, found {}",
+                                                     repr.size, repr.size, repr.queens.len())));
+        }
+
+        let mut q = NQueens::new_empty(repr.size);
+        for (column, &row) in repr.queens.iter().enumerate() {
+            q.try_set_option(column, row).map_err(D::Error::custom)?;
+        }
+        Ok(q)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json;
+
+    use {BoardError, NQueens};
+    use solvers::SolverError;
+    use solvers::fundamental::fundamental_solutions;
+    use solvers::genetic::{Genetic, GeneticRun};
+    use solvers::hill_climbing::{HillClimbing, HillClimbingSolution};
+    use solvers::simulated_annealing::{AnnealingRun, SimulatedAnnealing};
+    use solvers::tabu_search::{TabuRun, TabuSearch};
+
+    #[test]
+    pub fn test_json_shape() {
+        let mut q = NQueens::from([1,3,0,2]);
+        q.unset(2);
+        let json = serde_json::to_string(&q).unwrap();
+        assert!(json == r#"{"size":4,"queens":[1,3,null,2]}"#, "{}", json);
+
+        let q2: NQueens = serde_json::from_str(&json).unwrap();
+        assert!(q2 == q);
+
+        let empty = serde_json::to_string(&NQueens::new_empty(0)).unwrap();
+        assert!(empty == r#"{"size":0,"queens":[]}"#, "{}", empty);
+    }
+
+    #[test]
+    pub fn test_invalid_boards() {
+        let e = serde_json::from_str::<NQueens>(r#"{"size":3,"queens":[0,1]}"#).unwrap_err();
+        assert!(e.to_string().starts_with("expected 3 queens for a board of size 3, found 2"), "{}", e);
+
+        let e = serde_json::from_str::<NQueens>(r#"{"size":2,"queens":[0,2]}"#).unwrap_err();
+        assert!(e.to_string().starts_with("row 2 in column 1 is out of range for a board of size 2"), "{}", e);
+
+        assert!(serde_json::from_str::<NQueens>(r#"{"queens":[0]}"#).is_err());
+    }
+
+    #[test]
+    pub fn test_solver_results() {
+        let solutions = fundamental_solutions(6);
+        let json = serde_json::to_string(&solutions).unwrap();
+        assert!(json == r#"[{"board":{"size":6,"queens":[1,3,5,0,2,4]},"orbit_size":4}]"#, "{}", json);
+
+        let result: Result<NQueens, SolverError> = Err(SolverError::NoSolutionsExist);
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json == r#"{"Err":"NoSolutionsExist"}"#, "{}", json);
+        let back: Result<NQueens, SolverError> = serde_json::from_str(&json).unwrap();
+        assert!(back == result);
+    }
+
+    #[test]
+    pub fn test_solver_runs() {
+        let run = Genetic { seed: Some(2), ..Genetic::default() }.run(8);
+        let json = serde_json::to_string(&run).unwrap();
+        assert!(json.contains(r#""generations":[{"best_conflicts":"#), "{}", json);
+        assert!(serde_json::from_str::<GeneticRun>(&json).unwrap() == run);
+
+        let solution = HillClimbing { seed: Some(0), max_restarts: 100, ..HillClimbing::default() }.solve_with_stats(8).unwrap();
+        let json = serde_json::to_string(&solution).unwrap();
+        assert!(serde_json::from_str::<HillClimbingSolution>(&json).unwrap() == solution, "{}", json);
+
+        let run = SimulatedAnnealing { seed: Some(1), ..SimulatedAnnealing::default() }.run(8);
+        let json = serde_json::to_string(&run).unwrap();
+        assert!(serde_json::from_str::<AnnealingRun>(&json).unwrap() == run, "{}", json);
+
+        let run = TabuSearch { seed: Some(1), ..TabuSearch::default() }.run(8);
+        let json = serde_json::to_string(&run).unwrap();
+        assert!(serde_json::from_str::<TabuRun>(&json).unwrap() == run, "{}", json);
+    }
+
+    #[test]
+    pub fn test_board_errors() {
+        let e = BoardError::RowOutOfRange { column: 1, row: 5, size: 4 };
+        let json = serde_json::to_string(&e).unwrap();
+        assert!(json == r#"{"RowOutOfRange":{"column":1,"row":5,"size":4}}"#, "{}", json);
+        assert!(serde_json::from_str::<BoardError>(&json).unwrap() == e);
+
+        let e = "Q X\nQ X".parse::<NQueens>().unwrap_err();
+        let json = serde_json::to_string(&e).unwrap();
+        assert!(json == r#"{"line":2,"column":1,"kind":{"TwoQueensInColumn":{"column":0}}}"#, "{}", json);
+    }
+}
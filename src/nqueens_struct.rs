@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::iter::IntoIterator;
 use std::slice::{Iter, IterMut};
 
@@ -7,15 +8,18 @@ use rand::distributions::{IndependentSample, Range};
 
 use rayon::prelude::*;
 
-use super::NQueensSuccessorIter;
+use super::{BoardError, NQueensSuccessorIter, NQueensSwapSuccessorIter};
 
-#[derive(Debug, Clone)]
+/// Boards are ordered lexicographically by the rows of their columns, with an empty column coming
+/// before any row.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NQueens {
     queens: Vec<Option<usize>>,
 }
 
 impl NQueens {
     /// Creates a new board of size `usize` with None in each position
+    #[allow(clippy::redundant_field_names)]
     pub fn new_empty(size: usize) -> NQueens {
         let mut queens = Vec::new();
         for _ in 0..size {
@@ -29,11 +33,17 @@ impl NQueens {
 
     /// Creates a new board of size `size` with each queen's position selected uniformly at random
     pub fn new_random(size: usize) -> NQueens {
+        NQueens::new_random_with_rng(size, &mut rand::thread_rng())
+    }
+
+    /// Like `new_random`, but draws the positions from the given random number generator, so that
+    /// the board can be reproduced by seeding it.
+    #[allow(clippy::redundant_field_names)]
+    pub fn new_random_with_rng<R: Rng>(size: usize, rng: &mut R) -> NQueens {
         let mut queens = Vec::new();
 
-        let mut rng = rand::thread_rng();
         for _ in 0..size {
-            queens.push(Some(Range::new(0, size).ind_sample(&mut rng)));
+            queens.push(Some(Range::new(0, size).ind_sample(rng)));
         }
 
         NQueens {
@@ -44,9 +54,17 @@ impl NQueens {
     /// Creates a new board of size `size` with one queen per row and column, selected uniformly
     /// at random from the set of permutations of the set `0..size`
     pub fn new_random_permutation(size: usize) -> NQueens {
-        let mut queens: Vec<Option<usize>> = (0..size).map(|i| Some(i)).collect();
+        NQueens::new_random_permutation_with_rng(size, &mut rand::thread_rng())
+    }
 
-        let mut rng = rand::thread_rng();
+    /// Like `new_random_permutation`, but shuffles with the given random number generator.
+    ///
+    /// `HillClimbing`, `SimulatedAnnealing` and `TabuSearch` draw their starting board with this
+    /// before making any other random choices, so given generators in the same state they all
+    /// start from the same board.
+    #[allow(clippy::redundant_closure, clippy::redundant_field_names)]
+    pub fn new_random_permutation_with_rng<R: Rng>(size: usize, rng: &mut R) -> NQueens {
+        let mut queens: Vec<Option<usize>> = (0..size).map(|i| Some(i)).collect();
 
         rng.shuffle(&mut queens);
         
@@ -93,6 +111,19 @@ impl NQueens {
         &self.queens[column]
     }
 
+    /// Returns the position of the queen in the given column, or an error if the column is out
+    /// of bounds or has no queen in it.
+    pub fn try_get(&self, column: usize) -> Result<usize, BoardError> {
+        self.try_get_option(column)?.ok_or(BoardError::EmptyColumn { column })
+    }
+
+    /// Returns an Option containing the position of the queen in the given column, or an error if
+    /// the column is out of bounds.
+    pub fn try_get_option(&self, column: usize) -> Result<Option<usize>, BoardError> {
+        self.check_column(column)?;
+        Ok(self.queens[column])
+    }
+
 
     /// Sets the queen in the given column to the value of `row` directly.
     pub fn set(&mut self, column: usize, row: usize) {
@@ -103,6 +134,7 @@ impl NQueens {
     }
 
     /// Sets the queen in the given column to the value of `row` as an option.
+    #[allow(clippy::unnecessary_unwrap)]
     pub fn set_option(&mut self, column: usize, row: Option<usize>) {
         assert!(column < self.size());
         if row.is_some() {
@@ -112,12 +144,49 @@ impl NQueens {
         self.queens[column] = row;
     }
 
+    /// Sets the queen in the given column to the value of `row` directly, or returns an error
+    /// without changing the board if the column or row is out of bounds.
+    pub fn try_set(&mut self, column: usize, row: usize) -> Result<(), BoardError> {
+        self.try_set_option(column, Some(row))
+    }
+
+    /// Sets the queen in the given column to the value of `row` as an option, or returns an error
+    /// without changing the board if the column or row is out of bounds.
+    pub fn try_set_option(&mut self, column: usize, row: Option<usize>) -> Result<(), BoardError> {
+        self.check_column(column)?;
+        if let Some(row) = row {
+            if row >= self.size() {
+                return Err(BoardError::RowOutOfRange { column, row, size: self.size() });
+            }
+        }
+
+        self.queens[column] = row;
+        Ok(())
+    }
+
+    fn check_column(&self, column: usize) -> Result<(), BoardError> {
+        if column >= self.size() {
+            return Err(BoardError::ColumnOutOfBounds { column, size: self.size() });
+        }
+        Ok(())
+    }
+
     /// Sets the queen in the given column to a position selected uniformly at random
     pub fn set_random(&mut self, column: usize) {
-        let mut rng = rand::thread_rng();
+        self.set_random_with_rng(column, &mut rand::thread_rng());
+    }
 
+    /// Like `set_random`, but draws the position from the given random number generator.
+    pub fn set_random_with_rng<R: Rng>(&mut self, column: usize, rng: &mut R) {
         let size = self.size();
-        self.set(column, Range::new(0, size).ind_sample(&mut rng));
+        self.set(column, Range::new(0, size).ind_sample(rng));
+    }
+
+    /// Exchanges the contents of two columns.
+    pub fn swap(&mut self, first: usize, second: usize) {
+        assert!(first < self.size() && second < self.size());
+
+        self.queens.swap(first, second);
     }
 
     /// Removes the queen from the given column if there is one.
@@ -128,12 +197,12 @@ impl NQueens {
     }
 
     /// Returns an iterator over the columns of the board
-    pub fn iter(&self) -> Iter<Option<usize>> {
+    pub fn iter(&self) -> Iter<'_, Option<usize>> {
         self.queens.iter()
     }
 
     /// Returns a mutable iterator over the columns of the board
-    pub fn iter_mut(&mut self) -> IterMut<Option<usize>> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, Option<usize>> {
         self.queens.iter_mut()
     }
 
@@ -144,129 +213,168 @@ impl NQueens {
     /// E.g. if all columns are filled on an 8 by 8 board, the iterator will return 56 boards,
     /// while if no columns are filled it will return 0 boards, and if one is filled it will return
     /// 7.
-    pub fn successors_iter(&self) -> NQueensSuccessorIter {
+    #[allow(clippy::needless_borrow)]
+    pub fn successors_iter(&self) -> NQueensSuccessorIter<'_> {
         NQueensSuccessorIter::new(&self)
     }
 
-    /// Checks if the current configuration of the board is a valid solution
-    pub fn is_valid(&self) -> bool {
-        // Check if all entries are not None; this lets us use unwrap everywhere and provides a
-        // quick exit.
-        let full_board = self.queens.par_iter()
-                             .all(|q| !q.is_none());
-        if !full_board {
-            return false;
+    /// Creates a struct that implements `Iterator` which provides the successors of the current
+    /// board made by exchanging the contents of two columns, skipping pairs with the same
+    /// contents.
+    ///
+    /// E.g. a permutation board of size 8 has 8*7/2 = 28 such successors, all of them permutation
+    /// boards, so a local search which only uses them never has two queens in the same row.
+    pub fn swap_successors_iter(&self) -> NQueensSwapSuccessorIter<'_> {
+        NQueensSwapSuccessorIter::new(self)
+    }
+
+    /// Returns one of the boards from `successors_iter`, selected uniformly at random, or None if
+    /// there are none.
+    pub fn random_successor(&self) -> Option<NQueens> {
+        self.random_successor_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Like `random_successor`, but draws the successor with the given random number generator.
+    pub fn random_successor_with_rng<R: Rng>(&self, rng: &mut R) -> Option<NQueens> {
+        // every column with a queen has the same number of successors
+        let set_columns: Vec<usize> = (0..self.size()).filter(|&column| self.is_set(column)).collect();
+        if self.size() < 2 || set_columns.is_empty() {
+            return None;
         }
 
-        // do not need to check columns because by definition of our struct we only have one queen
-        // per column
+        let column = set_columns[rng.gen_range(0, set_columns.len())];
+        let mut row = rng.gen_range(0, self.size()-1);
+        if row >= self.get(column) {
+            row += 1;
+        }
+        let mut successor = self.clone();
+        successor.set(column, row);
+        Some(successor)
+    }
 
-        // check rows
-        let all_rows_distinct = self.queens.par_iter().enumerate().all( |(i,q)| {
-            let q = q.unwrap();
-            self.queens[i+1..].par_iter().all( |q2| {
-                let q2 = q2.unwrap();
-                q != q2
-            })
-        });
+    /// Returns one of the boards from `swap_successors_iter`, selected uniformly at random, or None
+    /// if there are none.
+    pub fn random_swap_successor(&self) -> Option<NQueens> {
+        self.random_swap_successor_with_rng(&mut rand::thread_rng())
+    }
 
-        if !all_rows_distinct {
-            return false;
+    /// Like `random_swap_successor`, but draws the successor with the given random number
+    /// generator.
+    pub fn random_swap_successor_with_rng<R: Rng>(&self, rng: &mut R) -> Option<NQueens> {
+        if self.queens.iter().all(|&row| row == self.queens[0]) {
+            return None;
         }
 
-        // check diagonals
-        // This "row+column are all distinct" test works because if you imagine an addition table,
-        // all the elements on a rising diagonal are the same. Similarly, all the elements on a
-        // falling diagonal are the same in a subtraction table. Additionally, they are symmetric
-        // (up to sign, see below) so we only have to do the computations for (i,j) pairs and not
-        // (j,i) as well.
-        let all_diagonals_distinct = self.queens.par_iter().enumerate().all( |(i, q)| {
-            let q = q.unwrap();
-            // optimization so we don't compare (i,j) and (j,i) as noted above
-            self.queens[i+1..].par_iter().enumerate().all( |(j, q2)| {
-                let q2 = q2.unwrap();
-                // The i+1 term appears to account for the shifting that we did in the second
-                // enumeration.
-                
-                // This should be correct for all useful inputs. We don't actually need the signed
-                // answer; we just don't want them to be equal. If the numbers are too large we may
-                // be incorrect (i.e. if things are around USIZE_MAX, USIZE_MAX/2 etc.) but that
-                // would be way too many queens.
-                (i+q != i+1+j+q2) && (i.wrapping_sub(q) != (i+1+j).wrapping_sub(q2))
-            })
-        });
+        // pairs of columns are drawn until they differ, which for a permutation board is always
+        // the first pair
+        loop {
+            let first = rng.gen_range(0, self.size());
+            let mut second = rng.gen_range(0, self.size()-1);
+            if second >= first {
+                second += 1;
+            }
+            if self.queens[first] != self.queens[second] {
+                let mut successor = self.clone();
+                successor.swap(first, second);
+                return Some(successor);
+            }
+        }
+    }
 
-        if !all_diagonals_distinct {
+    /// Checks if the current configuration of the board is a valid solution
+    pub fn is_valid(&self) -> bool {
+        // Check if all entries are not None; this provides a quick exit.
+        let full_board = self.queens.iter().all(|q| q.is_some());
+        if !full_board {
             return false;
         }
 
-        // if above checks pass, this is a valid solution and we return true
-        return true;
+        // do not need to check columns because by definition of our struct we only have one queen
+        // per column, so the board is valid exactly when no row or diagonal has two queens on it
+        self.count_conflicts() == 0
     }
 
-    // This logic is basically entirely duplicated, but I don't think there's a good way to
-    // deduplicate them because of the short-circuiting we can do in the validity checks vs.
-    // counting conflicts
-
     /// Counts the number of pairs of queens in conflict with each other, including queens passing
     /// through other queens.
     pub fn count_conflicts(&self) -> u32 {
-        // NOTE: the `|| 0u32` after the fold calls below is there because the ParallelIterator
-        // trait's fold takes an identity function rather than a value
+        // Every pair of queens on the same row or diagonal is a conflict, so a line with k queens
+        // on it contributes k choose 2 conflicts. No pair of queens is counted twice because two
+        // queens in different columns can only share one line: if they were on the same row and a
+        // diagonal, or on both diagonals, they would have to be in the same column.
+        let counts = self.line_counts();
+        counts.rows.iter()
+            .chain(counts.diagonals.iter())
+            .chain(counts.antidiagonals.iter())
+            .map(|&k| ((k as u64)*(k as u64).saturating_sub(1)/2) as u32)
+            .sum()
+    }
+
+    /// Counts the queens on each row and diagonal in a single pass over the columns. Large boards
+    /// are split between threads, but for small boards the overhead isn't worth it.
+    fn line_counts(&self) -> LineCounts {
+        let size = self.size();
+        if size < PARALLEL_THRESHOLD {
+            let mut counts = LineCounts::new(size);
+            for (column, q) in self.queens.iter().enumerate() {
+                if let Some(row) = *q {
+                    counts.add(column, row);
+                }
+            }
+            return counts;
+        }
 
-        // do not need to check columns because by definition of our struct we only have one queen
-        // per column
+        self.queens.par_iter().enumerate()
+            .fold(|| LineCounts::new(size), |mut counts, (column, q)| {
+                if let Some(row) = *q {
+                    counts.add(column, row);
+                }
+                counts
+            })
+            .reduce_with(LineCounts::merge)
+            .unwrap()
+    }
+}
 
-        // check rows
-        let row_conflicts = self.queens.par_iter().enumerate().fold(|| 0u32, |sum, (i,q)| {
-            if q.is_none() { return sum; }
-            let q = q.unwrap();
-            let qconflicts = self.queens[i+1..].par_iter().fold(|| 0u32, |suminner, q2| {
-                if q2.is_none() { return suminner; }
+/// Boards at least this large have their rows and diagonals counted in parallel.
+const PARALLEL_THRESHOLD: usize = 1 << 16;
 
-                let q2 = q2.unwrap();
-                if q != q2 { return suminner; }
-                else { return suminner+1; }
-            }).sum();
-            return qconflicts+sum;
-        }).sum();
-
-        // check diagonals
-        // This "row+column are all distinct" test works because if you imagine an addition table,
-        // all the elements on a rising diagonal are the same. Similarly, all the elements on a
-        // falling diagonal are the same in a subtraction table. Additionally, they are symmetric
-        // (up to sign, see below) so we only have to do the computations for (i,j) pairs and not
-        // (j,i) as well.
-        // Additionally, we don't undercount diagonals when checking both rising and falling
-        // diagonals in the same if statement because only one of the checks can fail at a time. If
-        // both failed that would mean we have a queen on a rising and falling diagonal equidistant
-        // from the queen we're checking, which would mean they are on the same column. We can
-        // only have one queen per column so this cannot happen.
-        let diagonal_conflicts = self.queens.par_iter().enumerate().fold(|| 0u32, |sum, (i, q)| {
-            if q.is_none() { return sum; }
-            let q = q.unwrap();
-            // optimization so we don't compare (i,j) and (j,i) as noted above
-            let qconflicts = self.queens[i+1..].par_iter().enumerate().fold(|| 0u32, |suminner, (j, q2)| {
-                if q2.is_none() { return suminner; }
-                let q2 = q2.unwrap();
-                // The i+1 term appears to account for the shifting that we did in the second
-                // enumeration.
-
-                // This should be correct for all useful inputs. We don't actually need the signed
-                // answer; we just don't want them to be equal. If the numbers are too large we may
-                // be incorrect (i.e. if things are around USIZE_MAX, USIZE_MAX/2 etc.) but that
-                // would be way too many queens.
-                if (i+q != i+1+j+q2) && (i.wrapping_sub(q) != (i+1+j).wrapping_sub(q2)) {
-                    return suminner;
-                }
-                else {
-                    return suminner+1;
-                }
-            }).sum();
-            return qconflicts+sum;
-        }).sum();
+/// A histogram of the number of queens on each row, diagonal and antidiagonal of a board.
+struct LineCounts {
+    rows: Vec<u32>,
+    // indexed by column+row, which is the same along a rising diagonal
+    diagonals: Vec<u32>,
+    // indexed by column+(size-1-row), which is the same along a falling diagonal
+    antidiagonals: Vec<u32>,
+}
 
-        return row_conflicts+diagonal_conflicts;
+impl LineCounts {
+    fn new(size: usize) -> LineCounts {
+        let diagonal_count = (2*size).saturating_sub(1);
+        LineCounts {
+            rows: vec![0; size],
+            diagonals: vec![0; diagonal_count],
+            antidiagonals: vec![0; diagonal_count],
+        }
+    }
+
+    fn add(&mut self, column: usize, row: usize) {
+        let size = self.rows.len();
+        self.rows[row] += 1;
+        self.diagonals[column+row] += 1;
+        self.antidiagonals[column+size-1-row] += 1;
+    }
+
+    fn merge(mut self, other: LineCounts) -> LineCounts {
+        for (a, b) in self.rows.iter_mut().zip(other.rows) {
+            *a += b;
+        }
+        for (a, b) in self.diagonals.iter_mut().zip(other.diagonals) {
+            *a += b;
+        }
+        for (a, b) in self.antidiagonals.iter_mut().zip(other.antidiagonals) {
+            *a += b;
+        }
+        self
     }
 }
 
@@ -280,20 +388,49 @@ impl Index<usize> for NQueens {
 }
 
 /// Converts a slice of usize to an NQueens with the same number of elements as the slice, such
-/// that each column in order has a queen set in the given row. The trait documentation says it
-/// must not fail but since TryFrom isn't stabilized we still panic if one of the elements of the
-/// slice is larger than its length (i.e. it specifies a queen outside of the boundaries of the
-/// board).
-impl<T> From<T> for NQueens where T: AsRef<[usize]> {
-    fn from(slice: T) -> NQueens {
-        let slice = slice.as_ref();
+/// that each column in order has a queen set in the given row. Returns
+/// `BoardError::RowOutOfRange` if one of the elements of the slice is not less than its length
+/// (i.e. it specifies a queen outside of the boundaries of the board).
+impl<'a> TryFrom<&'a [usize]> for NQueens {
+    type Error = BoardError;
+
+    fn try_from(slice: &'a [usize]) -> Result<NQueens, BoardError> {
         let mut q = NQueens::new_empty(slice.len());
         for (i,&e) in slice.iter().enumerate() {
-            assert!(e < slice.len());
-            q.set(i, e);
+            q.try_set(i, e)?;
         }
 
-        q
+        Ok(q)
+    }
+}
+
+/// Panicking version of the `TryFrom<&[usize]>` conversion, shared by the `From` impls below.
+fn from_slice(slice: &[usize]) -> NQueens {
+    NQueens::try_from(slice).expect("row out of range")
+}
+
+// These `From` impls panic if one of the elements is larger than the length of the input, which
+// the trait documentation says they must not do. They predate `TryFrom`, which is what should be
+// used for untrusted input; a blanket `impl From<T: AsRef<[usize]>>` would conflict with it.
+
+/// Converts a Vec of usize to an NQueens as in `TryFrom<&[usize]>`, but panics on failure.
+impl From<Vec<usize>> for NQueens {
+    fn from(v: Vec<usize>) -> NQueens {
+        from_slice(&v)
+    }
+}
+
+/// Converts a Vec of usize to an NQueens as in `TryFrom<&[usize]>`, but panics on failure.
+impl<'a> From<&'a Vec<usize>> for NQueens {
+    fn from(v: &'a Vec<usize>) -> NQueens {
+        from_slice(v)
+    }
+}
+
+/// Converts an array of usize to an NQueens as in `TryFrom<&[usize]>`, but panics on failure.
+impl<const N: usize> From<[usize; N]> for NQueens {
+    fn from(array: [usize; N]) -> NQueens {
+        from_slice(&array)
     }
 }
 
@@ -319,12 +456,17 @@ impl<'board> IntoIterator for &'board mut NQueens {
 
 #[cfg(test)]
 mod test {
+    use std::convert::TryFrom;
+
     use super::NQueens;
+    use {BoardError, NQueensTracked};
+    use solvers::seeded_rng;
 
     // Note that the set(x, y) function is (column, row), so the Q in a diagram on the same line
     // as a set call is not necessarily being set by that call.
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_valid_1() {
         let q = NQueens::from([1,3,0,2]);
         // X X Q X
@@ -338,6 +480,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_valid_2() {
         let q = NQueens::from([2,0,3,1]);
         // X Q X X
@@ -351,6 +494,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_valid_3() {
         let q = NQueens::from([2,0,3,1,4]);
         // X Q X X X
@@ -365,6 +509,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_valid_4() {
         let q = NQueens::from([1,4,2,0,3]);
         // X X X Q X
@@ -380,6 +525,7 @@ mod test {
 
     // of course we have to test an actual 8 queens
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_valid_5() {
         let q = NQueens::from([3,5,7,1,6,0,2,4]);
        // X X X X X Q X X
@@ -398,6 +544,7 @@ mod test {
 
     // and another nonsymmetric one
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_valid_6() {
         let q = NQueens::from([7,1,4,2,0,6,3,5]);
         // X X X X Q X X X
@@ -416,6 +563,7 @@ mod test {
 
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_horizontals_1() {
         let q = NQueens::from([0,0]);
         // Q Q
@@ -426,6 +574,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_horizontals_2() {
         let q = NQueens::from([1,1]);
         // X X
@@ -436,6 +585,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_horizontals_3() {
         let q = NQueens::from([0,2,0]);
         // Q X Q
@@ -447,6 +597,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_horizontals_4() {
         let q = NQueens::from([1,3,0,5,1,4]);
         // X X Q X X X
@@ -461,6 +612,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_horizontals_5() {
         let mut q = NQueens::new_empty(6);
         q.set(0, 0); // Q Q Q Q Q Q
@@ -475,6 +627,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_diagonals_1() {
         let q = NQueens::from([0,1]);
         // Q X
@@ -485,6 +638,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_diagonals_2() {
         let q = NQueens::from([1,0]);
         // X Q
@@ -495,6 +649,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_diagonals_3() {
         let q = NQueens::from([0,2,1]);
         // Q X X
@@ -506,6 +661,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_diagonals_4() {
         let q = NQueens::from([2,0,4,1,3]);
         // X Q X X X
@@ -519,6 +675,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_diagonals_5() {
         let q = NQueens::from([3,1,4,0,2]);
         // X X X Q X
@@ -532,6 +689,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_diagonals_6() {
         let q = NQueens::from([0,1,2,3,4]);
         // Q X X X X
@@ -545,6 +703,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison, clippy::identity_op)]
     pub fn test_diagonals_7() {
         let q = NQueens::from([0,1,2,1,0]);
         // Q X X X Q
@@ -558,6 +717,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison, clippy::identity_op)]
     pub fn test_diagonals_8() {
         let q = NQueens::from([0,1,2,3,4,1,0,7]);
         // Q X X X X X Q X
@@ -575,6 +735,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_conflicts_none_between() {
         // Q X Q
         // X X X
@@ -589,6 +750,7 @@ mod test {
 
     }
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_none_1() {
         let q = NQueens::new_empty(3);
         assert!(q.is_valid() == false);
@@ -597,6 +759,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_none_2() {
         let mut q = NQueens::from([0,2,0]);
         q.unset(2);
@@ -610,6 +773,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::partialeq_to_none)]
     pub fn test_iter_empty() {
         let q = NQueens::new_empty(0);
         assert!(q.iter().next() == None);
@@ -618,6 +782,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::partialeq_to_none)]
     pub fn test_iter_1() {
         let q = NQueens::from([2,0,3,1]);
 
@@ -651,6 +816,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::explicit_counter_loop)]
     pub fn test_intoiter() {
         let b = NQueens::new_random(4);
 
@@ -679,6 +845,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     pub fn test_random_permutation() {
         let queens = NQueens::new_random_permutation(8);
 
@@ -694,7 +861,110 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "assertion failed: e < slice.len()")]
+    pub fn test_random_with_rng() {
+        let a = NQueens::new_random_permutation_with_rng(20, &mut seeded_rng(7));
+        let b = NQueens::new_random_permutation_with_rng(20, &mut seeded_rng(7));
+        assert!(a == b);
+        assert!(a != NQueens::new_random_permutation_with_rng(20, &mut seeded_rng(8)));
+
+        let a = NQueens::new_random_with_rng(20, &mut seeded_rng(7));
+        assert!(a == NQueens::new_random_with_rng(20, &mut seeded_rng(7)));
+
+        let mut rng = seeded_rng(7);
+        let mut a = NQueens::new_empty(20);
+        let mut b = NQueens::new_empty(20);
+        a.set_random_with_rng(3, &mut rng);
+        let mut rng = seeded_rng(7);
+        b.set_random_with_rng(3, &mut rng);
+        assert!(a == b && a.is_set(3));
+    }
+
+    // the original pairwise definition of count_conflicts
+    fn count_conflicts_pairwise(q: &NQueens) -> u32 {
+        let mut count = 0;
+        for i in 0..q.size() {
+            for j in i+1..q.size() {
+                if let (Some(a), Some(b)) = (q.get_option(i), q.get_option(j)) {
+                    if a == b || i+a == j+b || i+b == j+a {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    pub fn test_conflicts_match_pairwise() {
+        for size in 0..12 {
+            for _ in 0..10 {
+                let mut q = NQueens::new_random(size);
+                if size > 0 {
+                    q.unset(size/2);
+                }
+                let count = q.count_conflicts();
+                let expected = count_conflicts_pairwise(&q);
+                assert!(count == expected, "{:?}: {} != {}", q, count, expected);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_large_board() {
+        // large enough to be counted in parallel
+        let size = 70_000;
+        let q = NQueens::new_random_permutation(size);
+        assert!(q.count_conflicts() == NQueensTracked::new(q.clone()).count_conflicts());
+
+        let mut diagonal = NQueens::new_empty(size);
+        for i in 0..size {
+            diagonal.set(i, i);
+        }
+        assert!(!diagonal.is_valid());
+        assert!(diagonal.count_conflicts() == ((size*(size-1))/2) as u32);
+    }
+
+    #[test]
+    pub fn test_try_from() {
+        let q = NQueens::try_from(&[1,3,0,2][..]).unwrap();
+        assert!(q == NQueens::from([1,3,0,2]));
+
+        let v = vec![1,2,3,4,5];
+        let err = NQueens::try_from(v.as_slice()).unwrap_err();
+        assert!(err == BoardError::RowOutOfRange { column: 4, row: 5, size: 5 }, "{:?}", err);
+
+        assert!(NQueens::try_from(&[0, 9][..]).is_err());
+        assert!(NQueens::try_from(&[][..]).unwrap().size() == 0);
+    }
+
+    #[test]
+    pub fn test_try_get() {
+        let mut q = NQueens::from([2,0,3,1]);
+        q.unset(1);
+        assert!(q.try_get(0) == Ok(2));
+        assert!(q.try_get(1) == Err(BoardError::EmptyColumn { column: 1 }));
+        assert!(q.try_get(4) == Err(BoardError::ColumnOutOfBounds { column: 4, size: 4 }));
+        assert!(q.try_get_option(1) == Ok(None));
+        assert!(q.try_get_option(7) == Err(BoardError::ColumnOutOfBounds { column: 7, size: 4 }));
+    }
+
+    #[test]
+    pub fn test_try_set() {
+        let mut q = NQueens::new_empty(4);
+        assert!(q.try_set(0, 3).is_ok());
+        assert!(q.get(0) == 3);
+
+        assert!(q.try_set(0, 4) == Err(BoardError::RowOutOfRange { column: 0, row: 4, size: 4 }));
+        assert!(q.try_set(4, 0) == Err(BoardError::ColumnOutOfBounds { column: 4, size: 4 }));
+        assert!(q.try_set_option(1, Some(9)).is_err());
+        assert!(q.try_set_option(0, None).is_ok());
+
+        // failed sets don't change the board
+        assert!(q == NQueens::new_empty(4));
+    }
+
+    #[test]
+    #[should_panic(expected = "RowOutOfRange { column: 4, row: 5, size: 5 }")]
     pub fn test_from_impl_fail() {
         let v = vec![1,2,3,4,5];
         let _ = NQueens::from(v);
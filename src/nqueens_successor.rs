@@ -11,7 +11,8 @@ pub struct NQueensSuccessorIter<'original> {
 }
 
 impl<'original> NQueensSuccessorIter<'original> {
-    pub fn new(original: &NQueens) -> NQueensSuccessorIter {
+    #[allow(clippy::redundant_field_names)]
+    pub fn new(original: &NQueens) -> NQueensSuccessorIter<'_> {
         let mut current_config = original.clone();
         let current_column = 0;
         if original.size() != 0 {
@@ -40,6 +41,7 @@ impl<'original> NQueensSuccessorIter<'original> {
 impl<'original> Iterator for NQueensSuccessorIter<'original> {
     type Item = NQueens;
 
+    #[allow(clippy::unnecessary_unwrap, clippy::assign_op_pattern, clippy::needless_return)]
     fn next(&mut self) -> Option<NQueens> {
         // no successors of a 1x1 board either way - either there's already a queen there or there
         // isn't, and we don't add queens to make a successor. 0 is trivial.
@@ -76,6 +78,7 @@ impl<'original> Iterator for NQueensSuccessorIter<'original> {
     }
 }
 
+#[allow(clippy::assign_op_pattern, clippy::needless_late_init)]
 fn next_column(col: Option<usize>, orig: Option<usize>, size: usize) -> Option<usize> {
     debug_assert!(size>0); // this is checked in NQueensSuccessorIter::next
     match (col, orig) {
@@ -108,9 +111,57 @@ fn next_column(col: Option<usize>, orig: Option<usize>, size: usize) -> Option<u
         _ => None
     }
 }
+
+/// An iterator which returns the boards obtained from the given board by exchanging the contents
+/// of two columns, in order of the first column and then the second. Pairs of columns with the
+/// same contents are skipped, since exchanging them gives the same board.
+///
+/// Exchanging columns never changes which rows are used, so the successors of a permutation board
+/// are all permutation boards.
+pub struct NQueensSwapSuccessorIter<'original> {
+    original: &'original NQueens,
+    first: usize,
+    second: usize,
+}
+
+impl<'original> NQueensSwapSuccessorIter<'original> {
+    pub fn new(original: &NQueens) -> NQueensSwapSuccessorIter<'_> {
+        NQueensSwapSuccessorIter {
+            original,
+            first: 0,
+            second: 1,
+        }
+    }
+}
+
+impl<'original> Iterator for NQueensSwapSuccessorIter<'original> {
+    type Item = NQueens;
+
+    fn next(&mut self) -> Option<NQueens> {
+        let size = self.original.size();
+        while self.first < size {
+            if self.second >= size {
+                self.first += 1;
+                self.second = self.first + 1;
+                continue;
+            }
+
+            let (first, second) = (self.first, self.second);
+            self.second += 1;
+            if self.original.get_option(first) != self.original.get_option(second) {
+                let mut successor = self.original.clone();
+                successor.swap(first, second);
+                return Some(successor);
+            }
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
     use NQueens;
+    use solvers::seeded_rng;
     #[test]
     pub fn test_successors_size0() {
         let q = NQueens::new_empty(0);
@@ -280,4 +331,78 @@ mod test {
         let count = q.successors_iter().count();
         assert!(count == 7);
     }
+
+    #[test]
+    pub fn test_swap_successors() {
+        let q = NQueens::from([1,3,0,2]);
+        let successors: Vec<NQueens> = q.swap_successors_iter().collect();
+        assert!(successors.len() == 4*3/2);
+        assert!(successors[0] == NQueens::from([3,1,0,2]));
+        assert!(successors[5] == NQueens::from([1,3,2,0]));
+        for successor in &successors {
+            let mut rows: Vec<usize> = successor.iter().map(|row| row.unwrap()).collect();
+            rows.sort();
+            assert!(rows == vec![0,1,2,3], "{:?}", successor);
+        }
+
+        let q = NQueens::new_random_permutation_with_rng(8, &mut seeded_rng(0));
+        assert!(q.swap_successors_iter().count() == 8*7/2);
+
+        assert!(NQueens::new_empty(0).swap_successors_iter().next().is_none());
+        assert!(NQueens::from([0]).swap_successors_iter().next().is_none());
+    }
+
+    #[test]
+    pub fn test_swap_successors_skip_same() {
+        // only the empty column can be exchanged with anything else
+        let mut q = NQueens::new_empty(4);
+        q.set(2, 1);
+        let successors: Vec<NQueens> = q.swap_successors_iter().collect();
+        assert!(successors.len() == 3);
+        assert!(successors.iter().all(|s| s.iter().filter(|row| row.is_some()).count() == 1));
+
+        assert!(NQueens::new_empty(4).swap_successors_iter().next().is_none());
+        assert!(NQueens::from([2,2,2]).swap_successors_iter().next().is_none());
+    }
+
+    #[test]
+    pub fn test_random_successors() {
+        let mut rng = seeded_rng(1);
+        let q = NQueens::new_random_permutation_with_rng(8, &mut rng);
+        let successors: Vec<NQueens> = q.successors_iter().collect();
+        let swap_successors: Vec<NQueens> = q.swap_successors_iter().collect();
+        for _ in 0..100 {
+            let successor = q.random_successor_with_rng(&mut rng).unwrap();
+            assert!(successors.contains(&successor), "{:?}", successor);
+            let successor = q.random_swap_successor_with_rng(&mut rng).unwrap();
+            assert!(swap_successors.contains(&successor), "{:?}", successor);
+        }
+
+        let mut q = NQueens::new_empty(4);
+        assert!(q.random_successor().is_none());
+        assert!(q.random_swap_successor().is_none());
+        q.set(2, 1);
+        let successor = q.random_successor().unwrap();
+        assert!(successor.get_option(2).is_some() && successor.get(2) != 1);
+        let successor = q.random_swap_successor().unwrap();
+        assert!(successor.get_option(2).is_none());
+
+        assert!(NQueens::from([0]).random_successor().is_none());
+        assert!(NQueens::new_empty(0).random_successor().is_none());
+        assert!(NQueens::new_empty(0).random_swap_successor().is_none());
+    }
+
+    #[test]
+    pub fn test_random_successors_uniform() {
+        // all 6 swap successors and all 12 successors of a 4 by 4 board should turn up
+        let mut rng = seeded_rng(2);
+        let q = NQueens::from([1,3,0,2]);
+        let mut swaps = ::std::collections::HashSet::new();
+        let mut moves = ::std::collections::HashSet::new();
+        for _ in 0..500 {
+            swaps.insert(q.random_swap_successor_with_rng(&mut rng).unwrap());
+            moves.insert(q.random_successor_with_rng(&mut rng).unwrap());
+        }
+        assert!(swaps.len() == 6 && moves.len() == 12);
+    }
 }
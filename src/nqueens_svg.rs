@@ -0,0 +1,216 @@
+use std::fmt::Write;
+
+use super::NQueens;
+
+/// Draws boards as SVG images, either one at a time with `render` or many at once in a grid with
+/// `render_sheet`. Squares are laid out the same way as `BoardRenderer` does, with row 0 at the
+/// top and the square at (column, row) dark when column+row is odd.
+#[derive(Debug, Clone)]
+pub struct SvgRenderer {
+    /// The width and height of each square, in pixels.
+    pub square_size: u32,
+    pub light_color: String,
+    pub dark_color: String,
+    pub queen_color: String,
+    /// Draw a line between the centres of every pair of queens in conflict with each other, so
+    /// that there are as many lines as `NQueens::count_conflicts`.
+    pub attack_lines: bool,
+    pub attack_color: String,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> SvgRenderer {
+        SvgRenderer {
+            square_size: 40,
+            light_color: "#f0d9b5".to_string(),
+            dark_color: "#b58863".to_string(),
+            queen_color: "#000000".to_string(),
+            attack_lines: false,
+            attack_color: "#d00000".to_string(),
+        }
+    }
+}
+
+impl SvgRenderer {
+    /// Draws the board as a standalone SVG document.
+    pub fn render(&self, board: &NQueens) -> String {
+        let width = self.board_width(board);
+
+        let mut svg = svg_header(width, width);
+        self.write_board(&mut svg, board, 0, 0);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Draws the boards in a grid with `columns` boards per row, each captioned with its position
+    /// in the list and its rows, e.g. `#1 [1,3,0,2]`.
+    pub fn render_sheet(&self, boards: &[NQueens], columns: usize) -> String {
+        let captions: Vec<String> = boards.iter().enumerate()
+            .map(|(i, q)| format!("#{} {}", i+1, q.to_row_list()))
+            .collect();
+        self.render_sheet_with_captions(boards, &captions, columns)
+    }
+
+    /// Draws the boards in a grid with `columns` boards per row, each with the corresponding
+    /// caption underneath. Panics if there isn't exactly one caption per board or if `columns`
+    /// is 0.
+    pub fn render_sheet_with_captions<S: AsRef<str>>(&self, boards: &[NQueens], captions: &[S], columns: usize) -> String {
+        assert!(boards.len() == captions.len(), "{} boards but {} captions", boards.len(), captions.len());
+        assert!(columns > 0);
+
+        // every cell of the grid is big enough for the largest board, with a gap of one square
+        // between cells and around the edge, and room for a line of text under each board
+        let gap = self.square_size;
+        let font_size = (self.square_size/2).max(10);
+        let cell_width = boards.iter().map(|q| self.board_width(q)).max().unwrap_or(0);
+        let cell_height = cell_width + 2*font_size;
+
+        let grid_columns = columns.min(boards.len()) as u32;
+        let grid_rows = boards.len().div_ceil(columns) as u32;
+        let width = gap + grid_columns*(cell_width+gap);
+        let height = gap + grid_rows*(cell_height+gap);
+
+        let mut svg = svg_header(width, height);
+        for (i, (board, caption)) in boards.iter().zip(captions).enumerate() {
+            let x = gap + (i % columns) as u32*(cell_width+gap);
+            let y = gap + (i / columns) as u32*(cell_height+gap);
+            // smaller boards are centred above their captions
+            self.write_board(&mut svg, board, x + (cell_width-self.board_width(board))/2, y);
+
+            let _ = writeln!(svg, r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" text-anchor="middle">{}</text>"#,
+                             x + cell_width/2, y + cell_width + font_size*3/2, font_size, escape(caption.as_ref()));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn board_width(&self, board: &NQueens) -> u32 {
+        board.size() as u32*self.square_size
+    }
+
+    /// Appends the elements for the board with its top left corner at (x, y).
+    fn write_board(&self, svg: &mut String, board: &NQueens, x: u32, y: u32) {
+        let size = board.size();
+        let s = self.square_size;
+        let centre = |column: usize, row: usize| (x + column as u32*s + s/2, y + row as u32*s + s/2);
+
+        // writing to a String can't fail, so the results of writeln! are ignored
+        let _ = writeln!(svg, r#"<g class="board">"#);
+        let width = self.board_width(board);
+        let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                         x, y, width, width, self.light_color);
+        for row in 0..size {
+            for column in 0..size {
+                if (column+row) % 2 == 1 {
+                    let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                                     x + column as u32*s, y + row as u32*s, s, s, self.dark_color);
+                }
+            }
+        }
+
+        let queens: Vec<(usize, usize)> = board.iter().enumerate()
+            .filter_map(|(column, row)| row.map(|row| (column, row)))
+            .collect();
+
+        if self.attack_lines {
+            for (i, &(c1, r1)) in queens.iter().enumerate() {
+                for &(c2, r2) in &queens[i+1..] {
+                    let same_diagonal = c2-c1 == r2.abs_diff(r1);
+                    if r1 == r2 || same_diagonal {
+                        let (x1, y1) = centre(c1, r1);
+                        let (x2, y2) = centre(c2, r2);
+                        let _ = writeln!(svg, r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}"/>"#,
+                                         x1, y1, x2, y2, self.attack_color, (s/16).max(1));
+                    }
+                }
+            }
+        }
+
+        for &(column, row) in &queens {
+            let (cx, cy) = centre(column, row);
+            let _ = writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                             cx, cy, s*3/8, self.queen_color);
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+}
+
+fn svg_header(width: u32, height: u32) -> String {
+    format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            width, height)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::SvgRenderer;
+    use NQueens;
+    use solvers::backtracking::backtracking_solutions;
+
+    #[test]
+    pub fn test_render() {
+        let q = NQueens::from([1,3,0,2]);
+        let svg = SvgRenderer::default().render(&q);
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="160" height="160""#), "{}", svg);
+        assert!(svg.ends_with("</svg>\n"));
+
+        // one background and 8 dark squares
+        assert!(svg.matches("<rect").count() == 9, "{}", svg);
+        assert!(svg.matches("<circle").count() == 4, "{}", svg);
+        assert!(svg.contains(r#"<circle cx="20" cy="60" r="15""#), "{}", svg);
+        assert!(!svg.contains("<line"));
+    }
+
+    #[test]
+    pub fn test_attack_lines() {
+        let renderer = SvgRenderer { attack_lines: true, ..SvgRenderer::default() };
+
+        let q = NQueens::from([0,2,1]);
+        let svg = renderer.render(&q);
+        assert!(svg.matches("<line").count() == 1, "{}", svg);
+        assert!(svg.contains(r#"<line x1="60" y1="100" x2="100" y2="60""#), "{}", svg);
+
+        let q = NQueens::from([0,0,0,0,1]);
+        let svg = renderer.render(&q);
+        assert!(svg.matches("<line").count() as u32 == q.count_conflicts(), "{}", svg);
+
+        let svg = renderer.render(&NQueens::from([1,3,0,2]));
+        assert!(!svg.contains("<line"));
+    }
+
+    #[test]
+    pub fn test_empty_columns() {
+        let mut q = NQueens::new_empty(3);
+        q.set(1, 2);
+        let svg = SvgRenderer::default().render(&q);
+        assert!(svg.matches("<circle").count() == 1);
+
+        let svg = SvgRenderer::default().render(&NQueens::new_empty(0));
+        assert!(!svg.contains("<circle"));
+    }
+
+    #[test]
+    pub fn test_sheet() {
+        let solutions = backtracking_solutions(8);
+        let svg = SvgRenderer::default().render_sheet(&solutions, 10);
+        assert!(svg.matches(r#"<g class="board">"#).count() == 92);
+        assert!(svg.matches("<circle").count() == 92*8);
+        assert!(svg.contains(">#1 [0,4,7,5,2,6,1,3]</text>"), "{}", svg);
+        assert!(svg.contains(">#92 [7,3,0,2,5,1,6,4]</text>"), "{}", svg);
+
+        // 10 boards of 320px and 11 gaps of 40px across, 10 rows of boards with 40px of caption
+        assert!(svg.contains(r#"width="3640" height="4040""#), "{}", &svg[..200]);
+    }
+
+    #[test]
+    pub fn test_sheet_captions() {
+        let boards = vec![NQueens::from([0]), NQueens::from([1,3,0,2])];
+        let svg = SvgRenderer::default().render_sheet_with_captions(&boards, &["a < b", "c & d"], 3);
+        assert!(svg.contains(">a &lt; b</text>"), "{}", svg);
+        assert!(svg.contains(">c &amp; d</text>"), "{}", svg);
+        assert!(svg.contains(r#"width="440" height="280""#), "{}", svg);
+    }
+}
@@ -0,0 +1,246 @@
+use super::NQueens;
+
+/// The eight symmetries of a square board, i.e. the dihedral group D4.
+///
+/// Writing a queen's position as (column, row) on a board of size n, with m = n-1, each symmetry
+/// moves it as follows:
+///
+/// - `Identity`: (c, r) -> (c, r)
+/// - `Rotate90`: (c, r) -> (m-r, c)
+/// - `Rotate180`: (c, r) -> (m-c, m-r)
+/// - `Rotate270`: (c, r) -> (r, m-c)
+/// - `ReflectColumns`, which reverses the order of the columns: (c, r) -> (m-c, r)
+/// - `ReflectRows`, which reverses the order of the rows: (c, r) -> (c, m-r)
+/// - `Transpose`, about the diagonal through (0, 0): (c, r) -> (r, c)
+/// - `AntiTranspose`, about the other diagonal: (c, r) -> (m-r, m-c)
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    ReflectColumns,
+    ReflectRows,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    /// All eight symmetries, starting with the identity
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::ReflectColumns,
+        Symmetry::ReflectRows,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /// Returns the position (column, row) that a queen at (`column`, `row`) is moved to on a
+    /// board of the given size.
+    pub fn map(&self, size: usize, column: usize, row: usize) -> (usize, usize) {
+        let m = size-1;
+        match *self {
+            Symmetry::Identity => (column, row),
+            Symmetry::Rotate90 => (m-row, column),
+            Symmetry::Rotate180 => (m-column, m-row),
+            Symmetry::Rotate270 => (row, m-column),
+            Symmetry::ReflectColumns => (m-column, row),
+            Symmetry::ReflectRows => (column, m-row),
+            Symmetry::Transpose => (row, column),
+            Symmetry::AntiTranspose => (m-row, m-column),
+        }
+    }
+}
+
+impl NQueens {
+    /// Returns the board obtained by applying the given symmetry. Symmetries which move rows to
+    /// columns (rotations by a quarter turn and transposes) panic if two queens share a row, since
+    /// they would end up in the same column.
+    pub fn apply_symmetry(&self, symmetry: Symmetry) -> NQueens {
+        let size = self.size();
+        let mut q = NQueens::new_empty(size);
+        for (column, row) in self.iter().enumerate() {
+            if let Some(row) = *row {
+                let (new_column, new_row) = symmetry.map(size, column, row);
+                assert!(!q.is_set(new_column), "two queens in row {} cannot be moved by {:?}", row, symmetry);
+                q.set(new_column, new_row);
+            }
+        }
+        q
+    }
+
+    /// Returns the board rotated by a quarter turn. See `Symmetry`.
+    pub fn rotate_90(&self) -> NQueens {
+        self.apply_symmetry(Symmetry::Rotate90)
+    }
+
+    /// Returns the board rotated by a half turn. See `Symmetry`.
+    pub fn rotate_180(&self) -> NQueens {
+        self.apply_symmetry(Symmetry::Rotate180)
+    }
+
+    /// Returns the board rotated by three quarter turns. See `Symmetry`.
+    pub fn rotate_270(&self) -> NQueens {
+        self.apply_symmetry(Symmetry::Rotate270)
+    }
+
+    /// Returns the board with the order of the columns reversed. See `Symmetry`.
+    pub fn reflect_columns(&self) -> NQueens {
+        self.apply_symmetry(Symmetry::ReflectColumns)
+    }
+
+    /// Returns the board with the order of the rows reversed. See `Symmetry`.
+    pub fn reflect_rows(&self) -> NQueens {
+        self.apply_symmetry(Symmetry::ReflectRows)
+    }
+
+    /// Returns the board reflected about the diagonal through (0, 0). See `Symmetry`.
+    pub fn transpose(&self) -> NQueens {
+        self.apply_symmetry(Symmetry::Transpose)
+    }
+
+    /// Returns the board reflected about the diagonal through (0, size-1). See `Symmetry`.
+    pub fn anti_transpose(&self) -> NQueens {
+        self.apply_symmetry(Symmetry::AntiTranspose)
+    }
+
+    /// Returns the images of the board under all eight symmetries, in the order of
+    /// `Symmetry::ALL`. The images are not deduplicated, so a symmetric board appears more than
+    /// once.
+    pub fn orbit(&self) -> Vec<NQueens> {
+        Symmetry::ALL.iter().map(|&s| self.apply_symmetry(s)).collect()
+    }
+
+    /// Returns the lexicographically smallest board, comparing columns in order, among the
+    /// images of this board under all eight symmetries. Two boards are equivalent under symmetry
+    /// exactly when they have the same canonical form.
+    pub fn canonical(&self) -> NQueens {
+        self.orbit().into_iter().min().unwrap()
+    }
+
+    /// Returns the symmetries which map the board to itself. This always includes
+    /// `Symmetry::Identity`.
+    pub fn fixing_symmetries(&self) -> Vec<Symmetry> {
+        Symmetry::ALL.iter().cloned().filter(|&s| self.apply_symmetry(s) == *self).collect()
+    }
+
+    /// Returns the number of distinct boards equivalent to this one under symmetry, which is 1,
+    /// 2, 4 or 8.
+    pub fn orbit_size(&self) -> usize {
+        8/self.fixing_symmetries().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::Symmetry;
+    use NQueens;
+    use solvers::brute_force::brute_force_solutions;
+
+    #[test]
+    pub fn test_rotate() {
+        let q = NQueens::from([2,0,3,1,4]);
+        // X Q X X X
+        // X X X Q X
+        // Q X X X X
+        // X X Q X X
+        // X X X X Q
+        let r = q.rotate_90();
+        // X X Q X X
+        // X X X X Q
+        // X Q X X X
+        // X X X Q X
+        // Q X X X X
+        assert!(r == NQueens::from([4,2,0,3,1]), "{:?}", r);
+        assert!(r.rotate_90() == q.rotate_180());
+        assert!(r.rotate_180() == q.rotate_270());
+        assert!(q.rotate_270().rotate_90() == q);
+    }
+
+    #[test]
+    pub fn test_reflect() {
+        let q = NQueens::from([3,5,7,1,6,0,2,4]);
+        assert!(q.reflect_columns() == NQueens::from([4,2,0,6,1,7,5,3]));
+        assert!(q.reflect_rows() == NQueens::from([4,2,0,6,1,7,5,3]));
+        assert!(q.transpose().transpose() == q);
+        assert!(q.anti_transpose() == q.transpose().rotate_180());
+        assert!(q.reflect_columns().reflect_rows() == q.rotate_180());
+    }
+
+    #[test]
+    pub fn test_symmetries_preserve_validity() {
+        for q in brute_force_solutions(6) {
+            for image in q.orbit() {
+                assert!(image.is_valid(), "{:?}", image);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_empty_columns() {
+        let mut q = NQueens::new_empty(4);
+        q.set(0, 2);
+        let t = q.transpose();
+        assert!(t.get(2) == 0);
+        assert!(!t.is_set(0) && !t.is_set(1) && !t.is_set(3));
+
+        // rows are preserved, so this is fine with repeated rows
+        let q = NQueens::from([0,0,1]);
+        assert!(q.reflect_columns() == NQueens::from([1,0,0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be moved by Transpose")]
+    pub fn test_transpose_repeated_rows() {
+        let q = NQueens::from([0,0,1]);
+        let _ = q.transpose();
+    }
+
+    #[test]
+    pub fn test_canonical() {
+        let q = NQueens::from([2,0,3,1]);
+        assert!(q.canonical() == NQueens::from([1,3,0,2]));
+        for image in q.orbit() {
+            assert!(image.canonical() == q.canonical());
+        }
+    }
+
+    #[test]
+    pub fn test_fixing_symmetries() {
+        let q = NQueens::from([1,3,0,2]);
+        let fixing = q.fixing_symmetries();
+        assert!(fixing == vec![Symmetry::Identity, Symmetry::Rotate90, Symmetry::Rotate180, Symmetry::Rotate270],
+                "{:?}", fixing);
+        assert!(q.orbit_size() == 2);
+
+        let q = NQueens::from([3,5,7,1,6,0,2,4]);
+        assert!(q.fixing_symmetries() == vec![Symmetry::Identity, Symmetry::Rotate180]);
+        assert!(q.orbit_size() == 4);
+
+        let q = NQueens::from([7,1,4,2,0,6,3,5]);
+        assert!(q.fixing_symmetries() == vec![Symmetry::Identity]);
+        assert!(q.orbit_size() == 8);
+
+        assert!(NQueens::from([0]).orbit_size() == 1);
+    }
+
+    #[test]
+    pub fn test_fundamental_counts() {
+        let fundamental = [1, 0, 0, 1, 2, 1, 6, 12];
+        for (i, &expected) in fundamental.iter().enumerate() {
+            let size = i+1;
+            let solutions = brute_force_solutions(size);
+            let canonical: HashSet<NQueens> = solutions.iter().map(|q| q.canonical()).collect();
+            assert!(canonical.len() == expected, "size {}: {} != {}", size, canonical.len(), expected);
+
+            // the orbits partition the solutions
+            let total: usize = canonical.iter().map(|q| q.orbit_size()).sum();
+            assert!(total == solutions.len(), "size {}", size);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use super::NQueens;
+
+/// A wrapper around `NQueens` which keeps count of the queens on each row, diagonal and
+/// antidiagonal, so that the number of conflicts can be read in O(1) and updated in O(1) as queens
+/// are moved. Conflicts are counted the same way as `NQueens::count_conflicts`, i.e. every pair of
+/// queens on the same line is counted, even if there are other queens between them.
+#[derive(Debug, Clone)]
+pub struct NQueensTracked {
+    board: NQueens,
+    rows: Vec<u32>,
+    // indexed by column+row
+    diagonals: Vec<u32>,
+    // indexed by column+(size-1-row)
+    antidiagonals: Vec<u32>,
+    conflicts: u32,
+}
+
+impl NQueensTracked {
+    /// Creates a new tracked board of size `size` with None in each position
+    pub fn new_empty(size: usize) -> NQueensTracked {
+        NQueensTracked::new(NQueens::new_empty(size))
+    }
+
+    /// Creates a tracked board from an existing board, counting the queens on each line in O(n).
+    pub fn new(board: NQueens) -> NQueensTracked {
+        let size = board.size();
+        let diagonal_count = (2*size).saturating_sub(1);
+        let mut tracked = NQueensTracked {
+            board: NQueens::new_empty(size),
+            rows: vec![0; size],
+            diagonals: vec![0; diagonal_count],
+            antidiagonals: vec![0; diagonal_count],
+            conflicts: 0,
+        };
+
+        for (column, row) in board.iter().enumerate() {
+            tracked.set_option(column, *row);
+        }
+        tracked
+    }
+
+    /// Returns a reference to the underlying board
+    pub fn board(&self) -> &NQueens {
+        &self.board
+    }
+
+    /// Consumes the wrapper and returns the underlying board
+    pub fn into_inner(self) -> NQueens {
+        self.board
+    }
+
+    /// Returns the size of the board i.e. the width and height, which are equal
+    pub fn size(&self) -> usize {
+        self.board.size()
+    }
+
+    /// Returns the position of the queen in the given column. Panics if queen is not set in that
+    /// column
+    pub fn get(&self, column: usize) -> usize {
+        self.board.get(column)
+    }
+
+    /// Returns an Option containing the position of the queen in the given column
+    pub fn get_option(&self, column: usize) -> Option<usize> {
+        self.board.get_option(column)
+    }
+
+    /// Sets the queen in the given column to the value of `row` directly, updating the counts in
+    /// O(1).
+    pub fn set(&mut self, column: usize, row: usize) {
+        self.set_option(column, Some(row));
+    }
+
+    /// Sets the queen in the given column to the value of `row` as an option, updating the counts
+    /// in O(1).
+    pub fn set_option(&mut self, column: usize, row: Option<usize>) {
+        self.unset(column);
+        if let Some(row) = row {
+            // count before setting, while the column is empty
+            self.conflicts += self.attackers(column, row);
+            self.board.set(column, row);
+            self.add_to_lines(column, row);
+        }
+    }
+
+    /// Removes the queen from the given column if there is one, updating the counts in O(1).
+    pub fn unset(&mut self, column: usize) {
+        if let Some(row) = self.board.get_option(column) {
+            self.board.unset(column);
+            self.remove_from_lines(column, row);
+            self.conflicts -= self.attackers(column, row);
+        }
+    }
+
+    /// Returns the number of pairs of queens in conflict with each other in O(1).
+    pub fn count_conflicts(&self) -> u32 {
+        self.conflicts
+    }
+
+    /// Returns true if the current configuration of the board is a valid solution, in O(n).
+    pub fn is_valid(&self) -> bool {
+        self.conflicts == 0 && self.board.iter().all(|q| q.is_some())
+    }
+
+    /// Returns the number of queens, not counting any queen in `column` itself, on the row and
+    /// diagonals passing through (`column`, `row`). This is the number of conflicts the queen in
+    /// `column` would be part of if it were moved to `row`.
+    pub fn attackers(&self, column: usize, row: usize) -> u32 {
+        let size = self.size();
+        let mut count = self.rows[row] + self.diagonals[column+row] + self.antidiagonals[column+size-1-row];
+        // a queen can only share a line with another square in its own column if it's on that
+        // square, in which case it's on all three lines
+        if self.board.get_option(column) == Some(row) {
+            count -= 3;
+        }
+        count
+    }
+
+    /// Returns true if the queen in the given column is in conflict with any other queen. Returns
+    /// false if the column is empty.
+    pub fn is_attacked(&self, column: usize) -> bool {
+        match self.board.get_option(column) {
+            Some(row) => self.attackers(column, row) > 0,
+            None => false,
+        }
+    }
+
+    /// Returns the number of conflicts the board would have if the queen in `column` were moved
+    /// to `row`, or removed if `row` is None, in O(1) and without changing the board.
+    pub fn conflicts_if_moved(&self, column: usize, row: Option<usize>) -> u32 {
+        let current = match self.board.get_option(column) {
+            Some(current) => self.attackers(column, current),
+            None => 0,
+        };
+        let moved = match row {
+            Some(row) => self.attackers(column, row),
+            None => 0,
+        };
+        self.conflicts - current + moved
+    }
+
+    fn add_to_lines(&mut self, column: usize, row: usize) {
+        let size = self.size();
+        self.rows[row] += 1;
+        self.diagonals[column+row] += 1;
+        self.antidiagonals[column+size-1-row] += 1;
+    }
+
+    fn remove_from_lines(&mut self, column: usize, row: usize) {
+        let size = self.size();
+        self.rows[row] -= 1;
+        self.diagonals[column+row] -= 1;
+        self.antidiagonals[column+size-1-row] -= 1;
+    }
+}
+
+impl From<NQueens> for NQueensTracked {
+    fn from(board: NQueens) -> NQueensTracked {
+        NQueensTracked::new(board)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NQueensTracked;
+    use NQueens;
+
+    #[test]
+    pub fn test_matches_count_conflicts() {
+        for _ in 0..20 {
+            let q = NQueens::new_random(9);
+            let tracked = NQueensTracked::new(q.clone());
+            assert!(tracked.count_conflicts() == q.count_conflicts(), "{:?}", q);
+        }
+
+        let q = NQueens::from([0,1,2,3,4,1,0,7]);
+        assert!(NQueensTracked::from(q).count_conflicts() == 20);
+    }
+
+    #[test]
+    pub fn test_updates() {
+        let mut tracked = NQueensTracked::new_empty(6);
+        let mut q = NQueens::new_empty(6);
+        let moves = [(0, Some(0)), (1, Some(0)), (2, Some(2)), (3, Some(5)), (1, Some(3)),
+                     (2, None), (4, Some(4)), (5, Some(1)), (0, Some(5)), (3, None)];
+        for &(column, row) in moves.iter() {
+            tracked.set_option(column, row);
+            q.set_option(column, row);
+            assert!(tracked.count_conflicts() == q.count_conflicts(), "{:?}", q);
+        }
+
+        tracked.unset(0);
+        q.unset(0);
+        assert!(tracked.count_conflicts() == q.count_conflicts(), "{:?}", q);
+        assert!(tracked.board().iter().eq(q.iter()));
+    }
+
+    #[test]
+    pub fn test_conflicts_if_moved() {
+        let q = NQueens::new_random(8);
+        let tracked = NQueensTracked::new(q.clone());
+        for column in 0..8 {
+            for row in 0..8 {
+                let mut moved = q.clone();
+                moved.set(column, row);
+                assert!(tracked.conflicts_if_moved(column, Some(row)) == moved.count_conflicts());
+            }
+            let mut removed = q.clone();
+            removed.unset(column);
+            assert!(tracked.conflicts_if_moved(column, None) == removed.count_conflicts());
+        }
+        assert!(tracked.count_conflicts() == q.count_conflicts());
+    }
+
+    #[test]
+    pub fn test_valid() {
+        let tracked = NQueensTracked::new(NQueens::from([3,5,7,1,6,0,2,4]));
+        assert!(tracked.is_valid());
+        assert!((0..8).all(|c| !tracked.is_attacked(c)));
+
+        let mut tracked = tracked;
+        tracked.unset(3);
+        assert!(!tracked.is_valid());
+        assert!(tracked.count_conflicts() == 0);
+    }
+}
@@ -0,0 +1,204 @@
+use rayon::prelude::*;
+
+use nqueens_struct::NQueens;
+use solvers::{Solver, SolverError};
+
+/// A `Solver` which places queens column by column, backtracking as soon as a partial placement
+/// has a conflict. See `backtracking_solutions`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Backtracking;
+
+impl Solver for Backtracking {
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        backtracking_solution(size)
+    }
+
+    fn all_solutions(&self, size: usize) -> Result<Vec<NQueens>, SolverError> {
+        Ok(backtracking_solutions(size))
+    }
+
+    fn count_solutions(&self, size: usize) -> Result<usize, SolverError> {
+        Ok(backtracking_count(size))
+    }
+}
+
+/// Finds the lexicographically first solution by backtracking search, or returns
+/// `SolverError::NoSolutionsExist` if the whole search tree is exhausted without finding one.
+pub fn backtracking_solution(size: usize) -> Result<NQueens, SolverError> {
+    let mut search = Search::new(size);
+    let mut solution = None;
+    search.run(0, &mut |q| {
+        solution = Some(q.clone());
+        false
+    });
+
+    solution.ok_or(SolverError::NoSolutionsExist)
+}
+
+/// Finds all solutions by backtracking search and collects them into a Vec, in lexicographic
+/// order.
+pub fn backtracking_solutions(size: usize) -> Vec<NQueens> {
+    let mut solutions = Vec::new();
+    backtracking_for_each(size, |q| {
+        solutions.push(q.clone());
+        true
+    });
+
+    solutions
+}
+
+/// Calls `visit` on each solution in lexicographic order as soon as it is found, without storing
+/// them. The search stops early if `visit` returns false.
+pub fn backtracking_for_each<F>(size: usize, mut visit: F) where F: FnMut(&NQueens) -> bool {
+    let mut search = Search::new(size);
+    search.run(0, &mut visit);
+}
+
+/// Counts all solutions by backtracking search without storing them. The subtrees for each
+/// position of the queen in the first column are searched in parallel.
+pub fn backtracking_count(size: usize) -> usize {
+    if size == 0 {
+        return 1;
+    }
+
+    (0..size).into_par_iter().map(|row| {
+        let mut search = Search::new(size);
+        search.place(0, row);
+
+        let mut count = 0;
+        search.run(1, &mut |_| {
+            count += 1;
+            true
+        });
+        count
+    }).sum()
+}
+
+/// The state of a backtracking search: the partial board plus which rows and diagonals are
+/// already attacked, so that checking a placement is O(1) rather than rescanning the board.
+struct Search {
+    board: NQueens,
+    rows: Vec<bool>,
+    // indexed by column+row
+    diagonals: Vec<bool>,
+    // indexed by column+(size-1-row)
+    antidiagonals: Vec<bool>,
+}
+
+impl Search {
+    fn new(size: usize) -> Search {
+        let diagonal_count = (2*size).saturating_sub(1);
+        Search {
+            board: NQueens::new_empty(size),
+            rows: vec![false; size],
+            diagonals: vec![false; diagonal_count],
+            antidiagonals: vec![false; diagonal_count],
+        }
+    }
+
+    fn is_free(&self, column: usize, row: usize) -> bool {
+        let size = self.board.size();
+        !self.rows[row] && !self.diagonals[column+row] && !self.antidiagonals[column+size-1-row]
+    }
+
+    fn place(&mut self, column: usize, row: usize) {
+        let size = self.board.size();
+        self.board.set(column, row);
+        self.rows[row] = true;
+        self.diagonals[column+row] = true;
+        self.antidiagonals[column+size-1-row] = true;
+    }
+
+    fn remove(&mut self, column: usize) {
+        let size = self.board.size();
+        let row = self.board.get(column);
+        self.board.unset(column);
+        self.rows[row] = false;
+        self.diagonals[column+row] = false;
+        self.antidiagonals[column+size-1-row] = false;
+    }
+
+    /// Fills in the columns from `column` onwards, calling `visit` on each solution found. If
+    /// `visit` returns false the search stops, and `run` returns false to propagate that up.
+    fn run<F>(&mut self, column: usize, visit: &mut F) -> bool where F: FnMut(&NQueens) -> bool {
+        let size = self.board.size();
+        if column == size {
+            return visit(&self.board);
+        }
+
+        for row in 0..size {
+            if !self.is_free(column, row) {
+                continue;
+            }
+
+            self.place(column, row);
+            let keep_going = self.run(column+1, visit);
+            self.remove(column);
+
+            if !keep_going {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{backtracking_solution, backtracking_solutions, backtracking_count, backtracking_for_each};
+    use solvers::SolverError;
+    use solvers::brute_force::brute_force_solutions;
+
+    #[test]
+    pub fn test_empty() {
+        let solution = backtracking_solution(0);
+        assert!(solution.unwrap().size() == 0);
+        assert!(backtracking_count(0) == 1);
+    }
+
+    #[test]
+    pub fn test_2_3() {
+        assert!(backtracking_solution(2).unwrap_err() == SolverError::NoSolutionsExist);
+        assert!(backtracking_solution(3).unwrap_err() == SolverError::NoSolutionsExist);
+        assert!(backtracking_solutions(3).is_empty());
+        assert!(backtracking_count(3) == 0);
+    }
+
+    #[test]
+    pub fn test_first_solution_8() {
+        let q = backtracking_solution(8).unwrap();
+        assert!(q.is_valid());
+
+        let rows: Vec<usize> = q.iter().map(|r| r.unwrap()).collect();
+        assert!(rows == vec![0,4,7,5,2,6,1,3], "{:?}", rows);
+    }
+
+    #[test]
+    pub fn test_matches_brute_force() {
+        for size in 1..8 {
+            let solutions = backtracking_solutions(size);
+            assert!(solutions.iter().all(|q| q.is_valid()));
+            assert!(solutions.len() == brute_force_solutions(size).len(), "size {}", size);
+            assert!(backtracking_count(size) == solutions.len(), "size {}", size);
+        }
+    }
+
+    #[test]
+    pub fn test_for_each_stops_early() {
+        let mut seen = Vec::new();
+        backtracking_for_each(8, |q| {
+            seen.push(q.clone());
+            seen.len() < 3
+        });
+        assert!(seen == backtracking_solutions(8)[..3].to_vec());
+    }
+
+    #[test]
+    pub fn test_counts() {
+        let counts = [1, 1, 0, 0, 2, 10, 4, 40, 92, 352, 724];
+        for (size, &expected) in counts.iter().enumerate() {
+            let count = backtracking_count(size);
+            assert!(count == expected, "size {}: {} != {}", size, count, expected);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use rayon::prelude::*;
+
+use nqueens_struct::NQueens;
+use solvers::{Solver, SolverError};
+
+/// The largest board size `bitmask_count` supports, since each row set is stored in a `u64`.
+pub const MAX_BITMASK_SIZE: usize = 64;
+
+/// A `Solver` which only counts solutions, using `bitmask_count`. Finding or enumerating
+/// solutions is unsupported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bitmask;
+
+impl Solver for Bitmask {
+    fn solve_one(&self, _size: usize) -> Result<NQueens, SolverError> {
+        Err(SolverError::Unsupported)
+    }
+
+    fn count_solutions(&self, size: usize) -> Result<usize, SolverError> {
+        if size > MAX_BITMASK_SIZE {
+            return Err(SolverError::Unsupported);
+        }
+        Ok(bitmask_count(size))
+    }
+}
+
+/// Counts the solutions for a board of the given size by backtracking over bitmasks of the rows
+/// and diagonals attacked by the queens placed so far. Panics if `size` is larger than
+/// `MAX_BITMASK_SIZE`.
+///
+/// Every solution with the first queen in the top half of its column has a mirror image with the
+/// first queen in the bottom half, so only the top half (plus the middle row on odd boards) is
+/// searched. The subtrees for each position of the first queen are searched in parallel.
+pub fn bitmask_count(size: usize) -> usize {
+    assert!(size <= MAX_BITMASK_SIZE);
+    if size == 0 {
+        return 1;
+    }
+
+    let all = if size == MAX_BITMASK_SIZE { !0u64 } else { (1u64 << size) - 1 };
+
+    let count_first = |row: usize| {
+        let bit = 1u64 << row;
+        count_from(all, bit, bit << 1, bit >> 1)
+    };
+
+    let half: usize = (0..size/2).into_par_iter().map(&count_first).sum();
+    let middle = if size % 2 == 1 { count_first(size/2) } else { 0 };
+
+    2*half + middle
+}
+
+/// Counts the ways to fill the remaining columns, where `rows` is the set of rows already taken
+/// and `down`/`up` are the sets of rows attacked in the current column along each diagonal.
+fn count_from(all: u64, rows: u64, down: u64, up: u64) -> usize {
+    if rows == all {
+        return 1;
+    }
+
+    let mut count = 0;
+    let mut free = all & !(rows | down | up);
+    while free != 0 {
+        // lowest set bit
+        let bit = free & free.wrapping_neg();
+        free ^= bit;
+        count += count_from(all, rows | bit, ((down | bit) << 1) & all, (up | bit) >> 1);
+    }
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bitmask_count, Bitmask};
+    use solvers::{Solver, SolverError};
+    use solvers::brute_force::brute_force_solutions;
+
+    #[test]
+    pub fn test_matches_brute_force() {
+        for size in 0..9 {
+            let count = bitmask_count(size);
+            let expected = brute_force_solutions(size).len();
+            assert!(count == expected, "size {}: {} != {}", size, count, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_counts() {
+        let counts = [1, 1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200];
+        for (size, &expected) in counts.iter().enumerate() {
+            let count = bitmask_count(size);
+            assert!(count == expected, "size {}: {} != {}", size, count, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_solver() {
+        assert!(Bitmask.count_solutions(8).unwrap() == 92);
+        assert!(Bitmask.count_solutions(65).unwrap_err() == SolverError::Unsupported);
+        assert!(Bitmask.solve_one(8).unwrap_err() == SolverError::Unsupported);
+        assert!(Bitmask.all_solutions(8).unwrap_err() == SolverError::Unsupported);
+    }
+}
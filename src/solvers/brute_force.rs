@@ -1,34 +1,227 @@
-use permutohedron;
+use std::vec;
+
+use permutohedron::LexicalPermutation;
 
 use rayon::prelude::*;
+use rayon::par_iter::internal::{bridge_unindexed, UnindexedConsumer, UnindexedProducer};
 
 use nqueens_struct::NQueens;
+use solvers::{Solver, SolverError};
+
+/// A `Solver` which checks every permutation of `0..size` for validity. See
+/// `brute_force_solutions`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BruteForce;
 
+impl Solver for BruteForce {
+    /// Returns the first valid permutation found, or `SolverError::NoSolutionsExist` if none of
+    /// them are valid.
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        brute_force_iter(size).next().ok_or(SolverError::NoSolutionsExist)
+    }
+
+    fn all_solutions(&self, size: usize) -> Result<Vec<NQueens>, SolverError> {
+        Ok(brute_force_solutions(size))
+    }
+}
 
 /// Finds all solutions to the n-queens problem via brute force, by generating all permutations of
-/// 1..size and checking each one for validity, and collecting into a Vec.
+/// 1..size and checking each one for validity, and collecting into a Vec. The solutions are in
+/// lexicographic order.
 pub fn brute_force_solutions(size: usize) -> Vec<NQueens> {
-    let mut range: Vec<usize> = (0..size).collect();
-    let permutations: Vec<NQueens> = permutohedron::Heap::new(&mut range).map(|v| NQueens::from(v)).collect();
+    brute_force_par_iter(size).collect()
+}
 
-    let solutions: Vec<NQueens> = permutations.into_par_iter()
-        .filter(|q| q.is_valid())
-        .collect();
+/// Returns an iterator which lazily checks each permutation of 0..size in lexicographic order and
+/// yields the ones which are valid solutions. Only one permutation is held in memory at a time.
+pub fn brute_force_iter(size: usize) -> BruteForceIter {
+    BruteForceIter::new(size, vec![Vec::new()])
+}
+
+/// Returns a `ParallelIterator` over the same solutions as `brute_force_iter`. The permutations
+/// are split into subtrees by fixing the rows of the first few columns, and each subtree is
+/// searched lazily on its own thread. Collecting it into a Vec gives the solutions in
+/// lexicographic order.
+pub fn brute_force_par_iter(size: usize) -> BruteForceParIter {
+    BruteForceParIter {
+        size,
+        prefixes: vec![Vec::new()],
+    }
+}
 
-    solutions
+/// An iterator over the valid permutations of 0..size which start with one of a list of
+/// prefixes. See `brute_force_iter`.
+pub struct BruteForceIter {
+    size: usize,
+    prefixes: vec::IntoIter<Vec<usize>>,
+    // the permutation to be checked next and the length of the prefix it started from
+    current: Option<(Vec<usize>, usize)>,
+}
+
+impl BruteForceIter {
+    fn new(size: usize, prefixes: Vec<Vec<usize>>) -> BruteForceIter {
+        BruteForceIter {
+            size,
+            prefixes: prefixes.into_iter(),
+            current: None,
+        }
+    }
+
+    /// Advances `current` to the next permutation, moving on to the next prefix when the
+    /// current one is used up. Returns false when there are no permutations left.
+    fn advance(&mut self) -> bool {
+        if let Some((ref mut permutation, fixed)) = self.current {
+            if permutation[fixed..].next_permutation() {
+                return true;
+            }
+        }
+
+        match self.prefixes.next() {
+            Some(prefix) => {
+                let fixed = prefix.len();
+                let mut permutation = prefix;
+                let rest: Vec<usize> = (0..self.size).filter(|r| !permutation.contains(r)).collect();
+                permutation.extend(rest);
+                self.current = Some((permutation, fixed));
+                true
+            }
+            None => {
+                self.current = None;
+                false
+            }
+        }
+    }
+}
+
+impl Iterator for BruteForceIter {
+    type Item = NQueens;
+
+    fn next(&mut self) -> Option<NQueens> {
+        // the first call to advance moves onto the first prefix
+        while self.advance() {
+            let q = NQueens::from(&self.current.as_ref().unwrap().0);
+            if q.is_valid() {
+                return Some(q);
+            }
+        }
+        None
+    }
+}
+
+/// A `ParallelIterator` over the valid permutations of 0..size. See `brute_force_par_iter`.
+pub struct BruteForceParIter {
+    size: usize,
+    prefixes: Vec<Vec<usize>>,
+}
+
+impl ParallelIterator for BruteForceParIter {
+    type Item = NQueens;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+        where C: UnindexedConsumer<Self::Item>
+    {
+        bridge_unindexed(self, consumer)
+    }
+}
+
+impl UnindexedProducer for BruteForceParIter {
+    fn can_split(&self) -> bool {
+        // a single prefix can be split by fixing one more column, but there's no point once
+        // fewer than two columns are left free
+        self.prefixes.len() > 1 || self.prefixes[0].len() + 2 < self.size
+    }
+
+    fn split(mut self) -> (Self, Self) {
+        if self.prefixes.len() == 1 {
+            let prefix = self.prefixes.pop().unwrap();
+            // children in ascending order of the next row, to keep the overall order
+            // lexicographic
+            self.prefixes = (0..self.size).filter(|r| !prefix.contains(r)).map(|r| {
+                let mut child = prefix.clone();
+                child.push(r);
+                child
+            }).collect();
+        }
+
+        let mid = self.prefixes.len()/2;
+        let right = BruteForceParIter {
+            size: self.size,
+            prefixes: self.prefixes.split_off(mid),
+        };
+        (self, right)
+    }
+}
+
+impl IntoIterator for BruteForceParIter {
+    type Item = NQueens;
+    type IntoIter = BruteForceIter;
+
+    fn into_iter(self) -> BruteForceIter {
+        BruteForceIter::new(self.size, self.prefixes)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::brute_force_solutions;
+    use rayon::prelude::*;
+
+    use super::{brute_force_solutions, brute_force_iter, brute_force_par_iter, BruteForce};
+    use solvers::{Solver, SolverError};
 
     #[test]
+    #[allow(clippy::iter_count)]
     pub fn test_brute_force_count_4() {
        assert!(brute_force_solutions(4).iter().count() == 2);
     }
 
     #[test]
+    #[allow(clippy::iter_count)]
     pub fn test_brute_force_count_5() {
        assert!(brute_force_solutions(5).iter().count() == 10);
     }
+
+    #[test]
+    pub fn test_brute_force_solver() {
+        assert!(BruteForce.solve_one(6).unwrap().is_valid());
+        assert!(BruteForce.solve_one(2).unwrap_err() == SolverError::NoSolutionsExist);
+        assert!(BruteForce.count_solutions(6).unwrap() == 4);
+    }
+
+    #[test]
+    pub fn test_iter_lexicographic() {
+        let solutions: Vec<Vec<usize>> = brute_force_iter(6)
+            .map(|q| q.iter().map(|r| r.unwrap()).collect())
+            .collect();
+        assert!(solutions == vec![vec![1,3,5,0,2,4], vec![2,5,1,4,0,3],
+                                  vec![3,0,4,1,5,2], vec![4,2,0,5,3,1]], "{:?}", solutions);
+    }
+
+    #[test]
+    pub fn test_iter_take() {
+        let first: Vec<_> = brute_force_iter(8).take(3).collect();
+        assert!(first.len() == 3);
+        assert!(first.iter().all(|q| q.is_valid()));
+    }
+
+    #[test]
+    pub fn test_iter_trivial() {
+        assert!(brute_force_iter(0).count() == 1);
+        assert!(brute_force_iter(1).count() == 1);
+        assert!(brute_force_iter(2).count() == 0);
+        assert!(brute_force_iter(3).count() == 0);
+    }
+
+    #[test]
+    pub fn test_par_iter_matches_iter() {
+        for size in 0..9 {
+            let sequential: Vec<Vec<Option<usize>>> = brute_force_iter(size)
+                .map(|q| q.iter().cloned().collect())
+                .collect();
+            let parallel: Vec<Vec<Option<usize>>> = brute_force_par_iter(size)
+                .map(|q| q.iter().cloned().collect())
+                .collect();
+            assert!(sequential == parallel, "size {}", size);
+            assert!(brute_force_par_iter(size).count() == sequential.len(), "size {}", size);
+        }
+    }
 }
@@ -0,0 +1,262 @@
+use rayon::prelude::*;
+
+use nqueens_struct::NQueens;
+use nqueens_symmetry::Symmetry;
+use solvers::{Solver, SolverError};
+use solvers::bitmask::MAX_BITMASK_SIZE;
+
+/// A solution which is the canonical representative (see `NQueens::canonical`) of its class of
+/// solutions equivalent under symmetry, along with the number of solutions in that class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FundamentalSolution {
+    pub board: NQueens,
+    /// The number of distinct solutions equivalent to `board`, which is 1, 2, 4 or 8.
+    pub orbit_size: usize,
+}
+
+impl FundamentalSolution {
+    /// Returns every distinct solution equivalent to this one under symmetry, in lexicographic
+    /// order.
+    pub fn orbit(&self) -> Vec<NQueens> {
+        let mut orbit = self.board.orbit();
+        orbit.sort();
+        orbit.dedup();
+        orbit
+    }
+}
+
+/// A `Solver` which searches only for fundamental solutions and reconstructs the rest from their
+/// symmetries. See `fundamental_solutions`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fundamental;
+
+impl Solver for Fundamental {
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        checked_fundamental_solutions(size)?.into_iter().next()
+            .map(|f| f.board)
+            .ok_or(SolverError::NoSolutionsExist)
+    }
+
+    /// Returns all solutions, in lexicographic order.
+    fn all_solutions(&self, size: usize) -> Result<Vec<NQueens>, SolverError> {
+        let mut solutions: Vec<NQueens> = checked_fundamental_solutions(size)?.iter()
+            .flat_map(|f| f.orbit())
+            .collect();
+        solutions.sort();
+        Ok(solutions)
+    }
+
+    fn count_solutions(&self, size: usize) -> Result<usize, SolverError> {
+        Ok(checked_fundamental_solutions(size)?.iter().map(|f| f.orbit_size).sum())
+    }
+}
+
+/// Like `fundamental_solutions`, but returns `SolverError::Unsupported` instead of panicking if
+/// the board is too large.
+fn checked_fundamental_solutions(size: usize) -> Result<Vec<FundamentalSolution>, SolverError> {
+    if size > MAX_BITMASK_SIZE {
+        return Err(SolverError::Unsupported);
+    }
+    Ok(fundamental_solutions(size))
+}
+
+/// Finds the fundamental solutions for a board of the given size, i.e. one solution from each
+/// class of solutions which are equivalent under rotation and reflection, in lexicographic order.
+/// Panics if `size` is larger than `MAX_BITMASK_SIZE`.
+///
+/// The search only looks for canonical solutions. A canonical board is lexicographically no
+/// larger than any of its images, so after each queen is placed the partial board is compared
+/// with the parts of its images which are already known (see `FundamentalSearch::compare`), and
+/// abandoned as soon as one of them is smaller. The first column of each image is determined by
+/// where the queens on the edges of the board are, so most of the pruning is of boards with a
+/// queen on an edge nearer a corner than the first queen is. Only the top half of the first
+/// column is searched, and the rows and diagonals are tracked with bitmasks as in
+/// `bitmask_count`.
+///
+/// This still visits around a fifth of the partial boards that `backtracking_count` does, rather
+/// than an eighth. On a 14 by 14 board it runs about ten times as fast as `backtracking_count`,
+/// but much of that is down to the bitmasks, and it is slower than `bitmask_count`.
+pub fn fundamental_solutions(size: usize) -> Vec<FundamentalSolution> {
+    assert!(size <= MAX_BITMASK_SIZE);
+    if size == 0 {
+        return vec![FundamentalSolution { board: NQueens::new_empty(0), orbit_size: 1 }];
+    }
+
+    let all = if size == MAX_BITMASK_SIZE { !0u64 } else { (1u64 << size) - 1 };
+    let m = size-1;
+    let per_first: Vec<Vec<FundamentalSolution>> = (0..m/2+1).into_par_iter().map(|first| {
+        let mut search = FundamentalSearch {
+            size,
+            rows: vec![first],
+            columns: vec![None; size],
+            solutions: Vec::new(),
+        };
+        search.columns[first] = Some(0);
+
+        let bit = 1u64 << first;
+        search.run(all, bit, (bit << 1) & all, bit >> 1, 0);
+        search.solutions
+    }).collect();
+
+    per_first.into_iter().flatten().collect()
+}
+
+/// The state of the search for the fundamental solutions with a given first queen: the rows of
+/// the queens placed so far, in column order, and the column of the queen in each row, so that
+/// the board can be compared with its images without building them.
+struct FundamentalSearch {
+    size: usize,
+    rows: Vec<usize>,
+    columns: Vec<Option<usize>>,
+    solutions: Vec<FundamentalSolution>,
+}
+
+impl FundamentalSearch {
+    /// Fills in the remaining columns, where `taken` is the set of rows already taken and
+    /// `down`/`up` are the sets of rows attacked in the next column along each diagonal, as in
+    /// `bitmask_count`. `larger` is the set of symmetries whose images are already known to be
+    /// larger than the board.
+    fn run(&mut self, all: u64, taken: u64, down: u64, up: u64, larger: u8) {
+        let column = self.rows.len();
+        if column == self.size {
+            // every image is now either larger or the same board
+            let fixing = 8 - larger.count_ones() as usize;
+            self.solutions.push(FundamentalSolution {
+                board: NQueens::from(&self.rows),
+                orbit_size: 8/fixing,
+            });
+            return;
+        }
+
+        let mut free = all & !(taken | down | up);
+        while free != 0 {
+            // lowest set bit, so that solutions are found in lexicographic order
+            let bit = free & free.wrapping_neg();
+            free ^= bit;
+            let row = bit.trailing_zeros() as usize;
+
+            self.rows.push(row);
+            self.columns[row] = Some(column);
+            if let Some(larger) = self.compare(larger) {
+                self.run(all, taken | bit, ((down | bit) << 1) & all, (up | bit) >> 1, larger);
+            }
+            self.rows.pop();
+            self.columns[row] = None;
+        }
+    }
+
+    /// Compares the partial board with its images under each symmetry not in `larger`, as far
+    /// as they are known. Returns None if some image is already smaller, so that the board can't
+    /// be completed to a canonical one, or else `larger` with the symmetries whose images are now
+    /// known to be larger added, as bits indexed like `Symmetry::ALL`. Once every column is
+    /// filled, the images of the other symmetries are the board itself.
+    ///
+    /// Column `j` of an image comes from either column `j` or `m-j` of the board, which is known if
+    /// that column is filled, or from the queen in row `j` or `m-j`, which is known if the row has
+    /// one. If it doesn't, that queen will be in one of the empty columns, which still bounds the
+    /// image when its row is `m-column`.
+    fn compare(&self, mut larger: u8) -> Option<u8> {
+        let filled = self.rows.len();
+        let m = self.size-1;
+        let row_of = |column: usize| self.rows.get(column).cloned();
+        // the largest `m-column` for an empty column, if there are any left
+        let largest_flipped = m.saturating_sub(filled);
+
+        for (i, &symmetry) in Symmetry::ALL.iter().enumerate().skip(1) {
+            if larger & (1 << i) != 0 {
+                continue;
+            }
+
+            for (j, &row) in self.rows.iter().enumerate() {
+                // the row of the queen in column j of the image if it is known, and the largest
+                // it can be
+                let (image, at_most) = match symmetry {
+                    Symmetry::Rotate180 => (row_of(m-j).map(|r| m-r), m),
+                    Symmetry::ReflectColumns => (row_of(m-j), m),
+                    Symmetry::ReflectRows => (Some(m-row), m),
+                    Symmetry::Transpose => (self.columns[j], m),
+                    Symmetry::Rotate90 => (self.columns[m-j], m),
+                    Symmetry::Rotate270 => (self.columns[j].map(|c| m-c), largest_flipped),
+                    Symmetry::AntiTranspose => (self.columns[m-j].map(|c| m-c), largest_flipped),
+                    Symmetry::Identity => unreachable!(),
+                };
+
+                match image {
+                    Some(image) if image == row => continue,
+                    Some(image) if image < row => return None,
+                    Some(_) => larger |= 1 << i,
+                    None if at_most < row => return None,
+                    // it isn't known yet whether the image will be larger
+                    None => {}
+                }
+                break;
+            }
+        }
+        Some(larger)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::{fundamental_solutions, Fundamental};
+    use NQueens;
+    use solvers::{Solver, SolverError};
+    use solvers::backtracking::{backtracking_count, backtracking_solutions};
+
+    #[test]
+    pub fn test_fundamental_counts() {
+        let fundamental = [1, 1, 0, 0, 1, 2, 1, 6, 12, 46, 92];
+        for (size, &expected) in fundamental.iter().enumerate() {
+            let count = fundamental_solutions(size).len();
+            assert!(count == expected, "size {}: {} != {}", size, count, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_total_counts() {
+        for size in 0..11 {
+            let total = Fundamental.count_solutions(size).unwrap();
+            assert!(total == backtracking_count(size), "size {}", size);
+        }
+        assert!(Fundamental.count_solutions(65).unwrap_err() == SolverError::Unsupported);
+    }
+
+    #[test]
+    pub fn test_matches_canonical_forms() {
+        for size in 1..9 {
+            let expected: HashSet<NQueens> = backtracking_solutions(size).iter()
+                .map(|q| q.canonical())
+                .collect();
+            let found: HashSet<NQueens> = fundamental_solutions(size).into_iter()
+                .map(|f| f.board)
+                .collect();
+            assert!(found == expected, "size {}", size);
+        }
+    }
+
+    #[test]
+    pub fn test_all_solutions() {
+        for size in 0..9 {
+            let all = Fundamental.all_solutions(size).unwrap();
+            assert!(all == backtracking_solutions(size), "size {}", size);
+        }
+    }
+
+    #[test]
+    pub fn test_orbit_sizes() {
+        // the 8x8 board has one fundamental solution which is symmetric under a half turn
+        let orbit_sizes: Vec<usize> = fundamental_solutions(8).iter().map(|f| f.orbit_size).collect();
+        assert!(orbit_sizes.iter().filter(|&&s| s == 4).count() == 1, "{:?}", orbit_sizes);
+        assert!(orbit_sizes.iter().filter(|&&s| s == 8).count() == 11, "{:?}", orbit_sizes);
+
+        // the search works out the orbit sizes without building the images
+        for size in 1..11 {
+            for f in fundamental_solutions(size) {
+                assert!(f.orbit_size == f.board.orbit_size(), "{:?}", f);
+            }
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use rand;
+use rand::Rng;
+
+use nqueens_struct::NQueens;
+use solvers::{seeded_rng, Solver, SolverError};
+
+/// How two parent permutations are combined into a child. Each keeps the child a permutation, so
+/// no two queens ever share a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Crossover {
+    /// Partially mapped crossover: the child takes a random slice of columns from the first
+    /// parent, and the rest from the second parent, with rows that clash with the slice swapped
+    /// for the rows they were mapped to.
+    #[default]
+    PartiallyMapped,
+    /// The child takes a random slice of columns from the first parent, and the remaining rows in
+    /// the order they appear in the second parent, starting after the slice.
+    Order,
+    /// The columns are split into the cycles of positions that the two parents share rows
+    /// between, and the child takes alternate cycles from each parent. This uses no randomness.
+    Cycle,
+}
+
+/// How a child is changed after crossover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mutation {
+    /// Swap the rows of two random columns.
+    #[default]
+    Swap,
+    /// Reverse the rows of a random slice of columns.
+    Inversion,
+}
+
+/// How parents are picked from the population.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Pick `size` random boards, with replacement, and take the one with the fewest conflicts.
+    Tournament { size: usize },
+    /// Pick a random board with probability proportional to `1/(1 + conflicts)`.
+    Roulette,
+}
+
+impl Default for Selection {
+    fn default() -> Selection {
+        Selection::Tournament { size: 3 }
+    }
+}
+
+/// A `Solver` which evolves a population of random permutation boards, taking a board's number
+/// of conflicts as its fitness, so lower is better. See `Genetic::run`.
+#[derive(Debug, Clone, Copy)]
+pub struct Genetic {
+    pub population_size: usize,
+    /// The number of generations to breed before giving up.
+    pub max_generations: usize,
+    pub crossover: Crossover,
+    pub mutation: Mutation,
+    /// The probability that each child is mutated.
+    pub mutation_rate: f64,
+    pub selection: Selection,
+    /// The number of boards with the fewest conflicts which are copied unchanged into the next
+    /// generation.
+    pub elitism: usize,
+    /// Seed for the starting population and the breeding, so that runs can be reproduced. If
+    /// None, they are drawn from `rand::thread_rng()` every time.
+    pub seed: Option<u64>,
+}
+
+impl Default for Genetic {
+    fn default() -> Genetic {
+        Genetic {
+            population_size: 100,
+            max_generations: 1000,
+            crossover: Crossover::default(),
+            mutation: Mutation::default(),
+            mutation_rate: 0.8,
+            selection: Selection::default(),
+            elitism: 2,
+            seed: None,
+        }
+    }
+}
+
+/// The fitness of one generation of a `Genetic` run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GenerationStats {
+    /// The fewest conflicts on any board in the generation.
+    pub best_conflicts: u32,
+    /// The mean number of conflicts over the boards in the generation.
+    pub mean_conflicts: f64,
+}
+
+/// The best board found by a run of `Genetic`, which is a solution if `conflicts` is 0, along
+/// with the fitness of every generation, starting with the random initial population.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GeneticRun {
+    pub board: NQueens,
+    pub conflicts: u32,
+    pub generations: Vec<GenerationStats>,
+}
+
+impl Solver for Genetic {
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        if size == 2 || size == 3 {
+            return Err(SolverError::NoSolutionsExist);
+        }
+
+        let run = self.run(size);
+        if run.conflicts != 0 {
+            return Err(SolverError::BudgetExhausted);
+        }
+        Ok(run.board)
+    }
+}
+
+impl Genetic {
+    /// Evolves a population of boards of the given size until one has no conflicts or
+    /// `max_generations` generations have been bred, using the seed if there is one.
+    pub fn run(&self, size: usize) -> GeneticRun {
+        match self.seed {
+            Some(seed) => self.run_with_rng(size, &mut seeded_rng(seed)),
+            None => self.run_with_rng(size, &mut rand::thread_rng()),
+        }
+    }
+
+    /// Like `run`, but draws the population and makes the breeding choices with the given random
+    /// number generator. The starting population is drawn first, one board at a time with
+    /// `NQueens::new_random_permutation_with_rng`, so the first board is the one a hill climb
+    /// given a generator in the same state would start from.
+    ///
+    /// Panics if `population_size` is 0.
+    pub fn run_with_rng<R: Rng>(&self, size: usize, rng: &mut R) -> GeneticRun {
+        assert!(self.population_size > 0, "the population must not be empty");
+
+        let mut population: Vec<(NQueens, u32)> = (0..self.population_size)
+            .map(|_| {
+                let q = NQueens::new_random_permutation_with_rng(size, rng);
+                let conflicts = q.count_conflicts();
+                (q, conflicts)
+            })
+            .collect();
+        let mut generations = Vec::new();
+
+        loop {
+            // sorting is stable, so the elite are the same on every run with the same seed
+            population.sort_by_key(|&(_, conflicts)| conflicts);
+            let total: u32 = population.iter().map(|&(_, conflicts)| conflicts).sum();
+            generations.push(GenerationStats {
+                best_conflicts: population[0].1,
+                mean_conflicts: total as f64/population.len() as f64,
+            });
+            if population[0].1 == 0 || generations.len() > self.max_generations {
+                break;
+            }
+
+            let mut next: Vec<(NQueens, u32)> = population.iter().take(self.elitism).cloned().collect();
+            while next.len() < self.population_size {
+                let first = &population[self.select(&population, rng)].0;
+                let second = &population[self.select(&population, rng)].0;
+                let mut child = self.crossover.apply(first, second, rng);
+                if rng.gen::<f64>() < self.mutation_rate {
+                    self.mutation.apply(&mut child, rng);
+                }
+                let conflicts = child.count_conflicts();
+                next.push((child, conflicts));
+            }
+            population = next;
+        }
+
+        let (board, conflicts) = population.swap_remove(0);
+        GeneticRun {
+            board,
+            conflicts,
+            generations,
+        }
+    }
+
+    /// Returns the index of a parent chosen from the population.
+    fn select<R: Rng>(&self, population: &[(NQueens, u32)], rng: &mut R) -> usize {
+        match self.selection {
+            Selection::Tournament { size } => {
+                // the population is sorted, so the lowest index drawn is the fittest
+                (0..size.max(1)).map(|_| rng.gen_range(0, population.len())).min().unwrap()
+            }
+            Selection::Roulette => {
+                let weight = |conflicts: u32| 1.0/(1.0 + conflicts as f64);
+                let total: f64 = population.iter().map(|&(_, conflicts)| weight(conflicts)).sum();
+                let mut target = rng.gen::<f64>()*total;
+                for (i, &(_, conflicts)) in population.iter().enumerate() {
+                    target -= weight(conflicts);
+                    if target < 0.0 {
+                        return i;
+                    }
+                }
+                population.len() - 1
+            }
+        }
+    }
+}
+
+impl Crossover {
+    /// Breeds a child from two permutation boards of the same size. Panics if either board has
+    /// an empty column or their sizes differ.
+    pub fn apply<R: Rng>(&self, first: &NQueens, second: &NQueens, rng: &mut R) -> NQueens {
+        assert!(first.size() == second.size(), "can't cross boards of sizes {} and {}", first.size(), second.size());
+        let first: Vec<usize> = first.iter().map(|row| row.unwrap()).collect();
+        let second: Vec<usize> = second.iter().map(|row| row.unwrap()).collect();
+        let size = first.len();
+        if size == 0 {
+            return NQueens::new_empty(0);
+        }
+
+        let child = match *self {
+            Crossover::PartiallyMapped => {
+                let (start, end) = random_slice(size, rng);
+                partially_mapped(&first, &second, start, end)
+            }
+            Crossover::Order => {
+                let (start, end) = random_slice(size, rng);
+                order(&first, &second, start, end)
+            }
+            Crossover::Cycle => cycle(&first, &second),
+        };
+        NQueens::from(child)
+    }
+}
+
+impl Mutation {
+    /// Changes the rows of the board without adding or removing queens.
+    pub fn apply<R: Rng>(&self, board: &mut NQueens, rng: &mut R) {
+        let size = board.size();
+        if size < 2 {
+            return;
+        }
+
+        match *self {
+            Mutation::Swap => {
+                let a = rng.gen_range(0, size);
+                let mut b = rng.gen_range(0, size-1);
+                if b >= a {
+                    b += 1;
+                }
+                board.swap(a, b);
+            }
+            Mutation::Inversion => {
+                let (start, end) = random_slice(size, rng);
+                let rows: Vec<Option<usize>> = board.iter().cloned().collect();
+                for column in start..end {
+                    board.set_option(column, rows[start + end - 1 - column]);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the bounds of a random non-empty slice of columns.
+fn random_slice<R: Rng>(size: usize, rng: &mut R) -> (usize, usize) {
+    let start = rng.gen_range(0, size);
+    let end = rng.gen_range(start+1, size+1);
+    (start, end)
+}
+
+fn partially_mapped(first: &[usize], second: &[usize], start: usize, end: usize) -> Vec<usize> {
+    let mut column_in_second = vec![0; second.len()];
+    for (column, &row) in second.iter().enumerate() {
+        column_in_second[row] = column;
+    }
+
+    let mut child: Vec<Option<usize>> = vec![None; first.len()];
+    for column in start..end {
+        child[column] = Some(first[column]);
+    }
+    for (column, &row) in second.iter().enumerate().take(end).skip(start) {
+        if first[start..end].contains(&row) {
+            continue;
+        }
+        // follow the mapping until it leads outside the slice
+        let mut target = column;
+        while target >= start && target < end {
+            target = column_in_second[first[target]];
+        }
+        child[target] = Some(row);
+    }
+    for (column, &row) in second.iter().enumerate() {
+        if child[column].is_none() {
+            child[column] = Some(row);
+        }
+    }
+    child.into_iter().map(|row| row.unwrap()).collect()
+}
+
+fn order(first: &[usize], second: &[usize], start: usize, end: usize) -> Vec<usize> {
+    let size = first.len();
+    let mut child = first.to_vec();
+    let mut remaining = (0..size).map(|i| second[(end + i) % size]).filter(|row| !first[start..end].contains(row));
+    for i in 0..size - (end-start) {
+        child[(end + i) % size] = remaining.next().unwrap();
+    }
+    child
+}
+
+fn cycle(first: &[usize], second: &[usize]) -> Vec<usize> {
+    let mut column_in_first = vec![0; first.len()];
+    for (column, &row) in first.iter().enumerate() {
+        column_in_first[row] = column;
+    }
+
+    let mut child: Vec<Option<usize>> = vec![None; first.len()];
+    let mut from_first = true;
+    for start in 0..first.len() {
+        if child[start].is_some() {
+            continue;
+        }
+        let parent = if from_first { first } else { second };
+        let mut column = start;
+        while child[column].is_none() {
+            child[column] = Some(parent[column]);
+            column = column_in_first[second[column]];
+        }
+        from_first = !from_first;
+    }
+    child.into_iter().map(|row| row.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Crossover, Genetic, Mutation, Selection};
+    use super::{cycle, order, partially_mapped};
+    use NQueens;
+    use solvers::{seeded_rng, Solver, SolverError};
+
+    fn is_permutation(rows: &[usize]) -> bool {
+        let mut sorted = rows.to_vec();
+        sorted.sort();
+        sorted == (0..rows.len()).collect::<Vec<usize>>()
+    }
+
+    #[test]
+    pub fn test_crossover_examples() {
+        let first = [0, 1, 2, 3, 4, 5, 6, 7];
+        let second = [2, 4, 6, 0, 7, 5, 3, 1];
+
+        let child = partially_mapped(&first, &second, 3, 6);
+        assert!(child == vec![2, 7, 6, 3, 4, 5, 0, 1], "{:?}", child);
+
+        let child = order(&first, &second, 3, 6);
+        assert!(child == vec![6, 0, 7, 3, 4, 5, 1, 2], "{:?}", child);
+
+        let child = cycle(&first, &second);
+        assert!(child == vec![0, 4, 2, 3, 7, 5, 6, 1], "{:?}", child);
+        assert!(cycle(&first, &first) == first.to_vec());
+    }
+
+    #[test]
+    pub fn test_operators_keep_permutations() {
+        let mut rng = seeded_rng(0);
+        for size in 1..12 {
+            for _ in 0..50 {
+                let first = NQueens::new_random_permutation_with_rng(size, &mut rng);
+                let second = NQueens::new_random_permutation_with_rng(size, &mut rng);
+                for &crossover in &[Crossover::PartiallyMapped, Crossover::Order, Crossover::Cycle] {
+                    let mut child = crossover.apply(&first, &second, &mut rng);
+                    let rows: Vec<usize> = child.iter().map(|row| row.unwrap()).collect();
+                    assert!(is_permutation(&rows), "{:?} of {:?} and {:?} gave {:?}", crossover, first, second, child);
+
+                    for &mutation in &[Mutation::Swap, Mutation::Inversion] {
+                        mutation.apply(&mut child, &mut rng);
+                        let rows: Vec<usize> = child.iter().map(|row| row.unwrap()).collect();
+                        assert!(is_permutation(&rows), "{:?} gave {:?}", mutation, child);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_solutions() {
+        for &crossover in &[Crossover::PartiallyMapped, Crossover::Order, Crossover::Cycle] {
+            for &mutation in &[Mutation::Swap, Mutation::Inversion] {
+                for &selection in &[Selection::Tournament { size: 3 }, Selection::Roulette] {
+                    let genetic = Genetic {
+                        crossover,
+                        mutation,
+                        selection,
+                        seed: Some(1),
+                        ..Genetic::default()
+                    };
+                    let run = genetic.run(8);
+                    assert!(run.conflicts == 0 && run.board.is_valid(), "{:?}: {:?}", genetic, run);
+                    assert!(genetic.run(8) == run);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_generations() {
+        let genetic = Genetic { seed: Some(2), ..Genetic::default() };
+        let run = genetic.run(16);
+        assert!(run.conflicts == 0, "{:?}", run);
+        assert!(run.generations.last().unwrap().best_conflicts == 0);
+
+        // with elitism the best board never gets worse
+        for pair in run.generations.windows(2) {
+            assert!(pair[1].best_conflicts <= pair[0].best_conflicts, "{:?}", run.generations);
+        }
+        for generation in &run.generations {
+            assert!(generation.mean_conflicts >= generation.best_conflicts as f64);
+        }
+
+        let genetic = Genetic { max_generations: 0, seed: Some(2), ..Genetic::default() };
+        let run = genetic.run(16);
+        assert!(run.generations.len() == 1 && run.conflicts != 0);
+        assert!(genetic.solve_one(16).unwrap_err() == SolverError::BudgetExhausted);
+    }
+}
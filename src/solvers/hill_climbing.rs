@@ -1,52 +1,412 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand;
+use rand::Rng;
+
+use nqueens_move::Move;
 use nqueens_struct::NQueens;
+use nqueens_tracked::NQueensTracked;
+use solvers::{seeded_rng, Solver, SolverError};
+use solvers::neighborhood::{apply_moves, undo_moves, ColumnMove, Neighborhood};
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
-pub enum GradientDescentErr {
-    NoSolutionsExist,
-    SolutionNotFound,
+/// A `Solver` which runs hill climbs from random boards. By default each climb is a steepest
+/// descent which gives up at the first local minimum, like `hill_climbing_solution`, but the way
+/// moves are chosen can be changed with `strategy`, climbs can be allowed to make sideways moves
+/// across plateaus, and the solver can restart from a new random board a number of times or until
+/// a time limit is reached.
+#[derive(Debug, Clone)]
+pub struct HillClimbing {
+    /// Seed for the random starting boards, so that runs can be reproduced. If None, new boards
+    /// are drawn from `rand::thread_rng()` every time.
+    pub seed: Option<u64>,
+    /// The number of times to start over from a new random board after reaching a local
+    /// minimum.
+    pub max_restarts: usize,
+    /// Stop restarting once this much time has passed since the first climb started, even if
+    /// `max_restarts` hasn't been reached.
+    pub time_limit: Option<Duration>,
+    /// The number of consecutive sideways moves, to a successor with the same number of conflicts,
+    /// allowed before a climb gives up on a plateau. With the default of 0 a climb stops as soon
+    /// as no successor is strictly better.
+    pub max_sideways: usize,
+    /// The number of most recently visited boards which a climb avoids moving back to, so that
+    /// sideways moves don't just go back and forth between the same few boards. If every
+    /// successor at least as good as the current board is tabu, the climb moves back to the one
+    /// which has been tabu the longest, counting it as a sideways move.
+    pub tabu_size: usize,
+    /// How each step of a climb chooses which successor to move to. The default is
+    /// `ClimbStrategy::SteepestAscent`.
+    pub strategy: ClimbStrategy,
+    /// The boards a climb can move to from the current one. The default is `ColumnMove`, which
+    /// moves one queen within its column.
+    pub neighborhood: Arc<dyn Neighborhood + Send + Sync>,
 }
 
-/// Finds a single solution by random gradient descent by generating a random instance and
-/// iteratatively looking at the successors of the instance and choosing the one with the fewest
-/// number of pairs of queens attacking each other. This either finds a solution or a local
-/// minimum, in which case we return a GradientDescentErr.
-pub fn hill_climbing_solution(size: usize) -> Result<NQueens, GradientDescentErr> {
-    let mut current_iter = NQueens::new_random_permutation(size);
-    if size < 2 {
-        return Ok(current_iter);
+impl Default for HillClimbing {
+    fn default() -> HillClimbing {
+        HillClimbing {
+            seed: None,
+            max_restarts: 0,
+            time_limit: None,
+            max_sideways: 0,
+            tabu_size: 0,
+            strategy: ClimbStrategy::default(),
+            neighborhood: Arc::new(ColumnMove),
+        }
     }
+}
+
+/// How a hill climb chooses which successor to move to. Successors are the neighbors of the
+/// current board in the climb's `neighborhood`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClimbStrategy {
+    /// Look at every successor and move to one with the fewest conflicts, breaking ties at
+    /// random. This is the default.
+    #[default]
+    SteepestAscent,
+    /// Look at every successor and move to one of those with fewer conflicts than the current
+    /// board at random, weighted by how many fewer conflicts they have. If there are none this
+    /// falls back to a sideways move like `SteepestAscent`.
+    Stochastic,
+    /// Try up to `max_samples` successors chosen at random and move to the first one with fewer
+    /// conflicts than the current board, or to the first one with as many if there are none.
+    /// Unlike the other strategies this doesn't generate every successor for each move, so it is
+    /// the only one which is practical on large boards.
+    FirstChoice { max_samples: usize },
+}
 
-    if size == 2 {
-        return Err(GradientDescentErr::NoSolutionsExist);
+/// A solution found by `HillClimbing`, along with how much work it took to find it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HillClimbingSolution {
+    pub board: NQueens,
+    /// The number of climbs which ended in a local minimum before the one which found `board`.
+    pub restarts: usize,
+    /// The number of moves made, over all climbs.
+    pub steps: usize,
+    /// The number of those moves which were sideways.
+    pub sideways_steps: usize,
+    /// The number of those sideways moves which went back to a tabu board.
+    pub tabu_steps: usize,
+}
+
+impl Solver for HillClimbing {
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        self.solve_with_stats(size).map(|solution| solution.board)
     }
-    if size == 3 {
-        return Err(GradientDescentErr::NoSolutionsExist);
+}
+
+impl HillClimbing {
+    /// Like `solve_one`, but returns the number of restarts and steps taken along with the
+    /// solution.
+    pub fn solve_with_stats(&self, size: usize) -> Result<HillClimbingSolution, SolverError> {
+        match self.seed {
+            Some(seed) => self.solve_with_rng(size, &mut seeded_rng(seed)),
+            None => self.solve_with_rng(size, &mut rand::thread_rng()),
+        }
     }
 
-    let mut conflicts = current_iter.count_conflicts();
-    while conflicts != 0 {
-        // iterator cannot be empty when size is nontrivial, so unwrapping is fine
-        let (min_succ, min_conflicts) = current_iter.successors_iter()
-            .map(|q| {let c = q.count_conflicts(); (q, c)}) // like this because we get borrowck errors otherwise
-            .min_by_key(|&(_,c)| c).unwrap();
+    /// Like `solve_with_stats`, but draws the starting boards from the given random number
+    /// generator instead of the seed.
+    ///
+    /// Returns `SolverError::SolutionNotFound` if no restarts are allowed and the first climb
+    /// ends in a local minimum, or `SolverError::BudgetExhausted` if restarts were allowed but
+    /// the restart or time budget ran out.
+    pub fn solve_with_rng<R: Rng>(&self, size: usize, rng: &mut R) -> Result<HillClimbingSolution, SolverError> {
+        if size == 2 || size == 3 {
+            return Err(SolverError::NoSolutionsExist);
+        }
+
+        let start_time = Instant::now();
+        let mut restarts = 0;
+        let mut steps = 0;
+        let mut sideways_steps = 0;
+        let mut tabu_steps = 0;
+        loop {
+            let start = NQueens::new_random_permutation_with_rng(size, rng);
+            let climb = self.climb(start, rng, &mut |_| {});
+            steps += climb.steps;
+            sideways_steps += climb.sideways_steps;
+            tabu_steps += climb.tabu_steps;
+            if climb.conflicts == 0 {
+                return Ok(HillClimbingSolution {
+                    board: climb.board,
+                    restarts,
+                    steps,
+                    sideways_steps,
+                    tabu_steps,
+                });
+            }
+
+            let out_of_time = self.time_limit.is_some_and(|limit| start_time.elapsed() >= limit);
+            if restarts == self.max_restarts || out_of_time {
+                break;
+            }
+            restarts += 1;
+        }
 
-        // by using >= we prevent getting stuck in loops on plateaus, but may miss solutions
-        // directly next to a plateau.
-        if min_conflicts >= conflicts {
-            return Err(GradientDescentErr::SolutionNotFound);
+        if self.max_restarts == 0 && self.time_limit.is_none() {
+            return Err(SolverError::SolutionNotFound);
         }
-        else {
+        Err(SolverError::BudgetExhausted)
+    }
+
+    /// Runs a single steepest descent from the given board, ending at a solution or when no
+    /// successor is better, or as good if sideways moves are still allowed. Tabu boards are only
+    /// moved to if every successor which is at least as good as the current board is tabu, and
+    /// then only the oldest of them. `visit` is called on each board the climb moves to.
+    fn climb<R: Rng>(&self, start: NQueens, rng: &mut R, visit: &mut dyn FnMut(&NQueens)) -> Climb {
+        let mut current_iter = start;
+        let mut conflicts = current_iter.count_conflicts();
+        let mut steps = 0;
+        let mut sideways_steps = 0;
+        let mut tabu_steps = 0;
+        let mut consecutive_sideways = 0;
+
+        // the recently visited boards in the order they were visited, and as a set for lookups
+        let mut tabu_order = VecDeque::new();
+        let mut tabu = HashSet::new();
+
+        while conflicts != 0 {
+            let neighborhood = &*self.neighborhood;
+            let mut next = match self.strategy {
+                ClimbStrategy::SteepestAscent => {
+                    steepest_successor(&current_iter, neighborhood, &tabu, rng)
+                }
+                ClimbStrategy::Stochastic => {
+                    stochastic_successor(&current_iter, neighborhood, conflicts, &tabu, rng)
+                }
+                ClimbStrategy::FirstChoice { max_samples } => {
+                    first_choice_successor(&current_iter, neighborhood, conflicts, &tabu,
+                                           max_samples, rng)
+                }
+            };
+
+            // rather than ending the climb on a plateau whose way out leads back through recently
+            // visited boards, go back to the one that was visited longest ago
+            let stuck = next.as_ref().is_none_or(|&(_, c)| c > conflicts);
+            if stuck && consecutive_sideways < self.max_sideways {
+                let oldest = oldest_tabu_successor(&current_iter, neighborhood, conflicts, &tabu_order);
+                if let Some(oldest) = oldest {
+                    tabu_order.retain(|q| *q != oldest);
+                    tabu.remove(&oldest);
+                    next = Some((oldest, conflicts));
+                    tabu_steps += 1;
+                }
+            }
+
+            let (min_succ, min_conflicts) = match next {
+                Some(min) => min,
+                None => break,
+            };
+
+            // without sideways moves, stopping at >= prevents getting stuck in loops on plateaus,
+            // but may miss solutions directly next to a plateau.
+            if min_conflicts < conflicts {
+                consecutive_sideways = 0;
+            }
+            else if min_conflicts == conflicts && consecutive_sideways < self.max_sideways {
+                consecutive_sideways += 1;
+                sideways_steps += 1;
+            }
+            else {
+                break;
+            }
+
+            if self.tabu_size > 0 {
+                if tabu_order.len() == self.tabu_size {
+                    let oldest = tabu_order.pop_front().unwrap();
+                    tabu.remove(&oldest);
+                }
+                tabu.insert(current_iter.clone());
+                tabu_order.push_back(current_iter);
+            }
+
+            visit(&min_succ);
             conflicts = min_conflicts;
             current_iter = min_succ;
+            steps += 1;
+        }
+
+        Climb {
+            board: current_iter,
+            conflicts,
+            steps,
+            sideways_steps,
+            tabu_steps,
+        }
+    }
+}
+
+/// Finds a single solution by random gradient descent by generating a random instance and
+/// iteratively looking at the successors of the instance and choosing the one with the fewest
+/// number of pairs of queens attacking each other, breaking ties at random. This either finds a
+/// solution or a local minimum, in which case we return `SolverError::SolutionNotFound`.
+pub fn hill_climbing_solution(size: usize) -> Result<NQueens, SolverError> {
+    hill_climbing_solution_with_rng(size, &mut rand::thread_rng())
+}
+
+/// Like `hill_climbing_solution`, but draws the starting board, and the choices between equally
+/// good successors, from the given random number generator.
+pub fn hill_climbing_solution_with_rng<R: Rng>(size: usize, rng: &mut R) -> Result<NQueens, SolverError> {
+    HillClimbing::default().solve_with_rng(size, rng).map(|solution| solution.board)
+}
+
+/// Returns a successor with the fewest conflicts out of those which aren't tabu.
+fn steepest_successor<R: Rng>(board: &NQueens, neighborhood: &dyn Neighborhood,
+                              tabu: &HashSet<NQueens>, rng: &mut R)
+                              -> Option<(NQueens, u32)> {
+    best_successor(board, neighborhood, |q| !tabu.contains(q), rng)
+}
+
+/// Returns the successor with the given number of conflicts which comes first in `tabu_order`,
+/// i.e. which was visited longest ago, or None if no such successor is tabu.
+fn oldest_tabu_successor(board: &NQueens, neighborhood: &dyn Neighborhood, conflicts: u32,
+                         tabu_order: &VecDeque<NQueens>) -> Option<NQueens> {
+    let tracked = NQueensTracked::new(board.clone());
+    let mut scratch = tracked.clone();
+    let mut oldest: Option<usize> = None;
+    neighborhood.for_each_neighbor(&tracked, &mut |moves: &[Move]| {
+        apply_moves(&mut scratch, moves);
+        if scratch.count_conflicts() == conflicts {
+            if let Some(i) = tabu_order.iter().position(|q| q == scratch.board()) {
+                oldest = Some(oldest.map_or(i, |oldest| oldest.min(i)));
+            }
+        }
+        undo_moves(&mut scratch, moves);
+    });
+    oldest.map(|i| tabu_order[i].clone())
+}
+
+/// Returns a successor with fewer conflicts than the current board, chosen with probability
+/// proportional to the difference, or falls back to `steepest_successor` if there are none.
+fn stochastic_successor<R: Rng>(board: &NQueens, neighborhood: &dyn Neighborhood,
+                                conflicts: u32, tabu: &HashSet<NQueens>, rng: &mut R)
+                                -> Option<(NQueens, u32)> {
+    // boards in the tabu list were visited before the current board and have at least as many
+    // conflicts, so improving successors are never tabu
+    let tracked = NQueensTracked::new(board.clone());
+    let mut scratch = tracked.clone();
+    let mut improving: Vec<(Vec<Move>, u32)> = Vec::new();
+    neighborhood.for_each_neighbor(&tracked, &mut |moves: &[Move]| {
+        apply_moves(&mut scratch, moves);
+        let c = scratch.count_conflicts();
+        undo_moves(&mut scratch, moves);
+        if c < conflicts {
+            improving.push((moves.to_vec(), c));
         }
+    });
+    if improving.is_empty() {
+        return steepest_successor(board, neighborhood, tabu, rng);
     }
-    // current_iter has zero conflicts
-    return Ok(current_iter);
+
+    let total: u32 = improving.iter().map(|&(_, c)| conflicts-c).sum();
+    let mut target = rng.gen_range(0, total);
+    for (moves, c) in improving {
+        let weight = conflicts-c;
+        if target < weight {
+            apply_moves(&mut scratch, &moves);
+            return Some((scratch.into_inner(), c));
+        }
+        target -= weight;
+    }
+    unreachable!("the weights add up to total")
+}
+
+/// Samples up to `max_samples` random successors which aren't tabu, returning the first with
+/// fewer conflicts than the current board, or else the first with as many. The conflicts of each
+/// sample are found by making its moves on an `NQueensTracked` and taking them back, so only the
+/// successors returned are built.
+fn first_choice_successor<R: Rng>(board: &NQueens, neighborhood: &dyn Neighborhood,
+                                  conflicts: u32, tabu: &HashSet<NQueens>, max_samples: usize,
+                                  rng: &mut R) -> Option<(NQueens, u32)> {
+    let mut scratch = NQueensTracked::new(board.clone());
+    let mut moves = Vec::new();
+    let mut sideways = None;
+    for _ in 0..max_samples {
+        neighborhood.random_neighbor(&scratch, rng, &mut moves);
+        if moves.is_empty() {
+            break;
+        }
+
+        apply_moves(&mut scratch, &moves);
+        let c = scratch.count_conflicts();
+        let candidate = c < conflicts || (c == conflicts && sideways.is_none());
+        let q = if candidate && !tabu.contains(scratch.board()) { Some(scratch.board().clone()) } else { None };
+        undo_moves(&mut scratch, &moves);
+
+        match q {
+            Some(q) if c < conflicts => return Some((q, c)),
+            Some(q) => sideways = Some((q, c)),
+            None => {}
+        }
+    }
+    sideways
+}
+
+/// Returns the successor with the fewest conflicts out of those for which `allowed` is true,
+/// choosing uniformly at random between ties, or None if none are allowed. Successors are scored
+/// by making their moves on an `NQueensTracked` and taking them back, and only the one returned
+/// is built.
+fn best_successor<A, R>(board: &NQueens, neighborhood: &dyn Neighborhood, allowed: A, rng: &mut R) -> Option<(NQueens, u32)>
+    where A: Fn(&NQueens) -> bool,
+          R: Rng
+{
+    let tracked = NQueensTracked::new(board.clone());
+    let mut scratch = tracked.clone();
+    let mut best: Option<(Vec<Move>, u32)> = None;
+    let mut ties = 0;
+    neighborhood.for_each_neighbor(&tracked, &mut |moves: &[Move]| {
+        apply_moves(&mut scratch, moves);
+        let c = scratch.count_conflicts();
+        let competitive = best.as_ref().is_none_or(|&(_, best_conflicts)| c <= best_conflicts);
+        let is_allowed = competitive && allowed(scratch.board());
+        undo_moves(&mut scratch, moves);
+        if !is_allowed {
+            return;
+        }
+
+        match best {
+            Some((_, best_conflicts)) if c == best_conflicts => {
+                // pick uniformly among the tied successors without storing them
+                ties += 1;
+                if rng.gen_range(0, ties) != 0 {
+                    return;
+                }
+            }
+            _ => ties = 1,
+        }
+        best = Some((moves.to_vec(), c));
+    });
+
+    best.map(|(moves, c)| {
+        apply_moves(&mut scratch, &moves);
+        (scratch.into_inner(), c)
+    })
+}
+
+/// The local minimum that a single climb ends in, and how it got there.
+struct Climb {
+    board: NQueens,
+    conflicts: u32,
+    steps: usize,
+    sideways_steps: usize,
+    tabu_steps: usize,
 }
 
 #[cfg(test)]
 mod test {
-    use super::{hill_climbing_solution, GradientDescentErr};
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::{first_choice_successor, hill_climbing_solution, hill_climbing_solution_with_rng,
+                stochastic_successor, ClimbStrategy, HillClimbing};
+    use NQueens;
+    use solvers::{seeded_rng, Solver, SolverError};
+    use solvers::neighborhood::{ColumnMove, ColumnSwap, MinConflictMove, Neighborhood, RowRotation};
 
     #[test]
     pub fn test_empty() {
@@ -59,14 +419,15 @@ mod test {
     pub fn test_2_3() {
         let solution = hill_climbing_solution(2);
         assert!(solution.is_err());
-        assert!(solution.unwrap_err() == GradientDescentErr::NoSolutionsExist, "No solutions for size 2 boards");
+        assert!(solution.unwrap_err() == SolverError::NoSolutionsExist, "No solutions for size 2 boards");
 
         let solution = hill_climbing_solution(3);
         assert!(solution.is_err());
-        assert!(solution.unwrap_err() == GradientDescentErr::NoSolutionsExist, "No solutions for size 3 boards");
+        assert!(solution.unwrap_err() == SolverError::NoSolutionsExist, "No solutions for size 3 boards");
     }
 
     #[test]
+    #[allow(clippy::unnecessary_unwrap)]
     pub fn test_3_size_8_solutions() {
         let mut solutions = Vec::new();
         while solutions.len() < 3 {
@@ -79,4 +440,185 @@ mod test {
             assert!(q.is_valid(), "{:?}", q);
         }
     }
+
+    #[test]
+    pub fn test_seeded() {
+        for seed in 0..20 {
+            let a = hill_climbing_solution_with_rng(8, &mut seeded_rng(seed));
+            let b = hill_climbing_solution_with_rng(8, &mut seeded_rng(seed));
+            assert!(a == b, "seed {}", seed);
+
+            let solver = HillClimbing { seed: Some(seed), ..HillClimbing::default() };
+            assert!(solver.solve_one(8) == a, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    pub fn test_restarts() {
+        let solver = HillClimbing { max_restarts: 1000, ..HillClimbing::default() };
+        for _ in 0..3 {
+            let solution = solver.solve_with_stats(8).unwrap();
+            assert!(solution.board.is_valid(), "{:?}", solution);
+            assert!(solution.steps > 0);
+        }
+
+        // a single climb succeeds about one time in seven, so some seed in the first few needs a
+        // restart
+        let restarts: Vec<usize> = (0..10)
+            .map(|seed| HillClimbing { seed: Some(seed), ..solver.clone() }.solve_with_stats(8).unwrap().restarts)
+            .collect();
+        assert!(restarts.iter().any(|&r| r > 0), "{:?}", restarts);
+    }
+
+    #[test]
+    pub fn test_restarts_seeded() {
+        let solver = HillClimbing { seed: Some(3), max_restarts: 1000, ..HillClimbing::default() };
+        assert!(solver.solve_with_stats(10) == solver.solve_with_stats(10));
+    }
+
+    #[test]
+    pub fn test_budget_exhausted() {
+        // size 30 boards almost never reach a solution from a random permutation
+        let solver = HillClimbing { max_restarts: 2, seed: Some(0), ..HillClimbing::default() };
+        assert!(solver.solve_with_stats(30).unwrap_err() == SolverError::BudgetExhausted);
+
+        // the first climb always runs, however small the time limit
+        let solver = HillClimbing { seed: Some(0), max_restarts: usize::MAX, time_limit: Some(Duration::from_millis(0)), ..HillClimbing::default() };
+        assert!(solver.solve_with_stats(30).unwrap_err() == SolverError::BudgetExhausted);
+
+        let solver = HillClimbing { seed: Some(0), ..HillClimbing::default() };
+        assert!(solver.solve_with_stats(30).unwrap_err() == SolverError::SolutionNotFound);
+    }
+
+    #[test]
+    pub fn test_sideways_moves() {
+        // the textbook comparison: a single climb on an 8x8 board succeeds about one time in
+        // seven, but almost always if it may make up to 100 sideways moves
+        let successes = |solver: HillClimbing| {
+            (0..200).filter(|&seed| HillClimbing { seed: Some(seed), ..solver.clone() }.solve_with_stats(8).is_ok()).count()
+        };
+        let plain = successes(HillClimbing::default());
+        let sideways = successes(HillClimbing { max_sideways: 100, ..HillClimbing::default() });
+        assert!(plain < 50, "{}", plain);
+        assert!(sideways > 180, "{}", sideways);
+
+        // a tabu list stops the climb going back and forth across a plateau, so it gets out more
+        // often
+        let tabu = successes(HillClimbing { max_sideways: 100, tabu_size: 10, ..HillClimbing::default() });
+        assert!(tabu >= sideways, "{} {}", tabu, sideways);
+    }
+
+    #[test]
+    pub fn test_tabu_revisited_only_as_fallback() {
+        let strategies = [ClimbStrategy::SteepestAscent, ClimbStrategy::Stochastic,
+                          ClimbStrategy::FirstChoice { max_samples: 100 }];
+        for &strategy in &strategies {
+            for seed in 0..20 {
+                let solver = HillClimbing { strategy, max_sideways: 100, tabu_size: 5, ..HillClimbing::default() };
+                let mut rng = seeded_rng(seed);
+                let mut path = vec![NQueens::new_random_permutation_with_rng(8, &mut rng)];
+                let climb = solver.climb(path[0].clone(), &mut rng, &mut |q| path.push(q.clone()));
+
+                // a move back to a board visited within the last `tabu_size` moves is only made
+                // when every other sideways move is tabu
+                let revisits = path.iter().enumerate().filter(|&(step, q)| {
+                    path[step.saturating_sub(solver.tabu_size)..step].contains(q)
+                }).count();
+                assert!(revisits <= climb.tabu_steps, "{:?} seed {}: {} {} {:?}",
+                        strategy, seed, revisits, climb.tabu_steps, path);
+                assert!(climb.tabu_steps <= climb.sideways_steps);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_sideways_steps() {
+        let solver = HillClimbing { max_sideways: 100, tabu_size: 10, max_restarts: 100, ..HillClimbing::default() };
+        let total: usize = (0..20).map(|seed| {
+            let solution = HillClimbing { seed: Some(seed), ..solver.clone() }.solve_with_stats(8).unwrap();
+            assert!(solution.board.is_valid());
+            assert!(solution.sideways_steps <= solution.steps);
+            assert!(solution.tabu_steps <= solution.sideways_steps);
+            solution.sideways_steps
+        }).sum();
+        assert!(total > 0);
+
+        let solver = HillClimbing { max_restarts: 1000, ..HillClimbing::default() };
+        assert!(solver.solve_with_stats(8).unwrap().sideways_steps == 0);
+    }
+
+    #[test]
+    pub fn test_strategies() {
+        let strategies = [ClimbStrategy::SteepestAscent, ClimbStrategy::Stochastic,
+                          ClimbStrategy::FirstChoice { max_samples: 100 }];
+        for &strategy in &strategies {
+            let solver = HillClimbing { strategy, seed: Some(1), max_restarts: 1000, ..HillClimbing::default() };
+            let solution = solver.solve_with_stats(8).unwrap();
+            assert!(solution.board.is_valid(), "{:?}", strategy);
+            assert!(solver.solve_with_stats(8) == Ok(solution), "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    pub fn test_stochastic_improves() {
+        let q = NQueens::from([0,1,2,3,4,5]);
+        let conflicts = q.count_conflicts();
+        let mut rng = seeded_rng(0);
+        let mut distinct = HashSet::new();
+        for _ in 0..100 {
+            let (next, c) = stochastic_successor(&q, &ColumnMove, conflicts, &HashSet::new(), &mut rng).unwrap();
+            assert!(c < conflicts && next.count_conflicts() == c);
+            distinct.insert(next);
+        }
+        assert!(distinct.len() > 1);
+    }
+
+    #[test]
+    pub fn test_first_choice() {
+        let q = NQueens::from([0,1,2,3,4,5]);
+        let conflicts = q.count_conflicts();
+        let mut rng = seeded_rng(0);
+        let (next, c) = first_choice_successor(&q, &ColumnMove, conflicts, &HashSet::new(), 100, &mut rng).unwrap();
+        assert!(c < conflicts && next.count_conflicts() == c);
+        assert!(first_choice_successor(&q, &ColumnMove, conflicts, &HashSet::new(), 0, &mut rng).is_none());
+
+        // large boards are practical since each move only looks at a few successors
+        let solver = HillClimbing {
+            strategy: ClimbStrategy::FirstChoice { max_samples: 100*100 },
+            max_sideways: 1000,
+            max_restarts: 10,
+            seed: Some(1),
+            ..HillClimbing::default()
+        };
+        assert!(solver.solve_with_stats(100).unwrap().board.is_valid());
+    }
+
+    #[test]
+    pub fn test_neighborhoods() {
+        let neighborhoods: Vec<Arc<dyn Neighborhood + Send + Sync>> = vec![
+            Arc::new(ColumnSwap),
+            Arc::new(RowRotation { k: 3 }),
+            Arc::new(MinConflictMove),
+        ];
+        for neighborhood in neighborhoods {
+            for &strategy in &[ClimbStrategy::SteepestAscent, ClimbStrategy::FirstChoice { max_samples: 100 }] {
+                let solver = HillClimbing {
+                    neighborhood: neighborhood.clone(),
+                    strategy,
+                    max_sideways: 20,
+                    max_restarts: 1000,
+                    seed: Some(2),
+                    ..HillClimbing::default()
+                };
+                let solution = solver.solve_with_stats(8).unwrap();
+                assert!(solution.board.is_valid(), "{:?} {:?}", neighborhood, strategy);
+            }
+        }
+
+        // swaps keep every climb on permutation boards
+        let solver = HillClimbing { neighborhood: Arc::new(ColumnSwap), max_restarts: 1000, seed: Some(3), ..HillClimbing::default() };
+        let mut rows: Vec<usize> = solver.solve_one(10).unwrap().iter().map(|row| row.unwrap()).collect();
+        rows.sort();
+        assert!(rows == (0..10).collect::<Vec<usize>>());
+    }
 }
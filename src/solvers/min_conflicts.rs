@@ -0,0 +1,207 @@
+use rand;
+use rand::Rng;
+
+use nqueens_struct::NQueens;
+use nqueens_tracked::NQueensTracked;
+use solvers::{seeded_rng, Solver, SolverError};
+
+/// The number of random rows tried for each column during the greedy initial placement before
+/// settling for one with a diagonal conflict.
+const GREEDY_ATTEMPTS: usize = 128;
+
+/// Small boards can get stuck cycling between a few near-solutions, so the search starts over
+/// with a new placement after this many moves (or `size` moves, if that's more) without finding
+/// a solution.
+const MIN_RESTART_STEPS: usize = 100;
+
+/// A `Solver` which runs the min-conflicts local search for at most `max_steps` moves. See
+/// `min_conflicts_solution`.
+#[derive(Debug, Clone, Copy)]
+pub struct MinConflicts {
+    pub max_steps: usize,
+    /// Seed for the random choices made by the search, so that runs can be reproduced. If None,
+    /// they are drawn from `rand::thread_rng()` every time.
+    pub seed: Option<u64>,
+}
+
+impl Default for MinConflicts {
+    fn default() -> MinConflicts {
+        MinConflicts {
+            max_steps: 100_000,
+            seed: None,
+        }
+    }
+}
+
+impl Solver for MinConflicts {
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        match self.seed {
+            Some(seed) => min_conflicts_solution_with_rng(size, self.max_steps, &mut seeded_rng(seed)),
+            None => min_conflicts_solution(size, self.max_steps),
+        }
+    }
+}
+
+/// Finds a single solution with the min-conflicts heuristic. Queens are first placed greedily,
+/// one per row, trying a number of random unused rows in each column for one with no diagonal
+/// conflicts. Then, repeatedly, a random queen in conflict is moved to the row in its column which
+/// is attacked by the fewest other queens, breaking ties randomly. If that doesn't find a solution
+/// within `size` moves, or 100 moves on boards smaller than that, the search restarts from a new
+/// placement.
+///
+/// The queens on each row and diagonal are counted by `NQueensTracked`, so each move costs O(n)
+/// and the greedy placement leaves few enough conflicts that boards with a million queens are
+/// solved in seconds. Returns `SolverError::BudgetExhausted` if no solution is found within
+/// `max_steps` moves.
+pub fn min_conflicts_solution(size: usize, max_steps: usize) -> Result<NQueens, SolverError> {
+    min_conflicts_solution_with_rng(size, max_steps, &mut rand::thread_rng())
+}
+
+/// Like `min_conflicts_solution`, but makes its random choices with the given random number
+/// generator.
+pub fn min_conflicts_solution_with_rng<R: Rng>(size: usize, max_steps: usize, rng: &mut R) -> Result<NQueens, SolverError> {
+    if size == 2 || size == 3 {
+        return Err(SolverError::NoSolutionsExist);
+    }
+
+    let restart_steps = size.max(MIN_RESTART_STEPS);
+
+    let mut board = greedy_placement(size, rng);
+
+    let mut conflicted: Vec<usize> = Vec::new();
+    let mut steps = 0;
+    let mut steps_since_restart = 0;
+    while board.count_conflicts() != 0 {
+        if steps_since_restart == restart_steps {
+            board = greedy_placement(size, rng);
+            conflicted.clear();
+            steps_since_restart = 0;
+            continue;
+        }
+
+        if conflicted.is_empty() {
+            conflicted = (0..size).filter(|&c| board.is_attacked(c)).collect();
+        }
+
+        // queens can stop being in conflict when others move, so drop those lazily
+        let index = rng.gen_range(0, conflicted.len());
+        let column = conflicted[index];
+        if !board.is_attacked(column) {
+            conflicted.swap_remove(index);
+            continue;
+        }
+
+        if steps == max_steps {
+            return Err(SolverError::BudgetExhausted);
+        }
+        steps += 1;
+        steps_since_restart += 1;
+
+        let current = board.get(column);
+        let mut best_row = current;
+        let mut best_attackers = board.attackers(column, current);
+        let mut ties = 1;
+        for row in 0..size {
+            let attackers = board.attackers(column, row);
+            if attackers < best_attackers {
+                best_row = row;
+                best_attackers = attackers;
+                ties = 1;
+            }
+            else if attackers == best_attackers && row != current {
+                // pick uniformly among the tied rows without storing them
+                ties += 1;
+                if rng.gen_range(0, ties) == 0 {
+                    best_row = row;
+                }
+            }
+        }
+        board.set(column, best_row);
+
+        // the queens attacking the new position weren't in conflict before and so might be
+        // missing from the list
+        if best_attackers > 0 {
+            conflicted.clear();
+        }
+    }
+
+    Ok(board.into_inner())
+}
+
+/// Places one queen in each row, trying up to `GREEDY_ATTEMPTS` of the unused rows at random in
+/// each column for one whose diagonals are free.
+fn greedy_placement<R: Rng>(size: usize, rng: &mut R) -> NQueensTracked {
+    let mut board = NQueensTracked::new_empty(size);
+    let mut unused: Vec<usize> = (0..size).collect();
+
+    for column in 0..size {
+        let mut index = 0;
+        for _ in 0..GREEDY_ATTEMPTS {
+            index = rng.gen_range(0, unused.len());
+            if board.attackers(column, unused[index]) == 0 {
+                break;
+            }
+        }
+
+        let row = unused.swap_remove(index);
+        board.set(column, row);
+    }
+    board
+}
+
+#[cfg(test)]
+mod test {
+    use super::{min_conflicts_solution, min_conflicts_solution_with_rng, MinConflicts};
+    use solvers::{seeded_rng, Solver, SolverError};
+
+    #[test]
+    pub fn test_trivial() {
+        assert!(min_conflicts_solution(0, 0).unwrap().size() == 0);
+        assert!(min_conflicts_solution(1, 0).unwrap().is_valid());
+        assert!(min_conflicts_solution(2, 100).unwrap_err() == SolverError::NoSolutionsExist);
+        assert!(min_conflicts_solution(3, 100).unwrap_err() == SolverError::NoSolutionsExist);
+    }
+
+    #[test]
+    pub fn test_small_solutions() {
+        for size in 4..20 {
+            let solver = MinConflicts { seed: Some(size as u64), ..MinConflicts::default() };
+            let q = solver.solve_one(size).unwrap();
+            assert!(q.is_valid(), "{:?}", q);
+        }
+    }
+
+    #[test]
+    pub fn test_seeded() {
+        let a = min_conflicts_solution_with_rng(200, 100_000, &mut seeded_rng(1));
+        let b = min_conflicts_solution_with_rng(200, 100_000, &mut seeded_rng(1));
+        assert!(a.is_ok() && a == b);
+
+        let solver = MinConflicts { seed: Some(1), ..MinConflicts::default() };
+        assert!(solver.solve_one(200) == a);
+        assert!(solver.solve_one(200) == solver.solve_one(200));
+    }
+
+    #[test]
+    pub fn test_size_1000() {
+        let q = min_conflicts_solution(1000, 100_000).unwrap();
+        assert!(q.size() == 1000);
+        assert!(q.count_conflicts() == 0);
+    }
+
+    #[test]
+    #[ignore]
+    pub fn test_size_1_000_000() {
+        // takes a few seconds in release builds, and much longer in debug ones
+        let q = min_conflicts_solution_with_rng(1_000_000, 1_000_000, &mut seeded_rng(0)).unwrap();
+        assert!(q.size() == 1_000_000);
+        assert!(q.is_valid());
+    }
+
+    #[test]
+    pub fn test_budget_exhausted() {
+        // the greedy placement is all but certain to leave some conflicts on a board this large
+        let solution = min_conflicts_solution(100_000, 0);
+        assert!(solution.unwrap_err() == SolverError::BudgetExhausted);
+    }
+}
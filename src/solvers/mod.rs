@@ -1,2 +1,190 @@
+use std::error::Error;
+use std::fmt;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use nqueens_struct::NQueens;
+
+pub mod backtracking;
+pub mod bitmask;
 pub mod brute_force;
+pub mod fundamental;
+pub mod genetic;
 pub mod hill_climbing;
+pub mod min_conflicts;
+pub mod neighborhood;
+pub mod simulated_annealing;
+pub mod tabu_search;
+
+/// Errors that can be returned by any `Solver`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SolverError {
+    /// There are no solutions for a board of the requested size, e.g. sizes 2 and 3.
+    NoSolutionsExist,
+    /// The solver gave up without finding a solution, e.g. local search reaching a local minimum.
+    SolutionNotFound,
+    /// The solver ran out of its iteration, restart or time budget.
+    BudgetExhausted,
+    /// The solver does not support the requested operation, e.g. enumerating all solutions with
+    /// a local search.
+    Unsupported,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match *self {
+            SolverError::NoSolutionsExist => "no solutions exist for this board size",
+            SolverError::SolutionNotFound => "solver gave up without finding a solution",
+            SolverError::BudgetExhausted => "solver exhausted its budget",
+            SolverError::Unsupported => "operation is not supported by this solver",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl Error for SolverError {}
+
+/// A common interface for the solvers in this module, so that they can be swapped for one
+/// another.
+///
+/// Only `solve_one` is required. By default `all_solutions` returns `SolverError::Unsupported`
+/// and `count_solutions` counts the boards returned by `all_solutions`.
+pub trait Solver {
+    /// Finds a single solution for a board of the given size.
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError>;
+
+    /// Finds every solution for a board of the given size.
+    fn all_solutions(&self, _size: usize) -> Result<Vec<NQueens>, SolverError> {
+        Err(SolverError::Unsupported)
+    }
+
+    /// Counts the solutions for a board of the given size.
+    fn count_solutions(&self, size: usize) -> Result<usize, SolverError> {
+        self.all_solutions(size).map(|solutions| solutions.len())
+    }
+}
+
+/// Creates the random number generator used by the randomized solvers when they are given a
+/// seed. This is an `Isaac64Rng` seeded with the whole 64-bit seed, unlike `StdRng` whose word
+/// size depends on the platform, so the same seed gives the same sequence on 32-bit and 64-bit
+/// targets alike (for a given version of `rand`). A run can be replayed by passing its seed to
+/// `_with_rng` functions through this.
+pub fn seeded_rng(seed: u64) -> Isaac64Rng {
+    Isaac64Rng::from_seed(&[seed][..])
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use NQueens;
+    use super::{seeded_rng, Solver, SolverError};
+    use super::brute_force::BruteForce;
+    use super::genetic::Genetic;
+    use super::hill_climbing::HillClimbing;
+    use super::neighborhood::{ColumnMove, MinConflictMove, Neighborhood, RowRotation};
+    use super::simulated_annealing::SimulatedAnnealing;
+    use super::tabu_search::TabuSearch;
+
+    /// The randomized solvers which are expected to solve any board of a moderate size with their
+    /// default settings, seeded with the given seed.
+    fn randomized_solvers(seed: u64) -> Vec<(&'static str, Box<dyn Solver>)> {
+        vec![
+            ("simulated annealing", Box::new(SimulatedAnnealing { seed: Some(seed), ..SimulatedAnnealing::default() })),
+            ("genetic", Box::new(Genetic { seed: Some(seed), ..Genetic::default() })),
+            ("tabu search", Box::new(TabuSearch { seed: Some(seed), ..TabuSearch::default() })),
+        ]
+    }
+
+    /// The solvers which search a `Neighborhood`, searching the given one and seeded with the
+    /// given seed.
+    fn neighborhood_solvers(neighborhood: Arc<dyn Neighborhood + Send + Sync>, seed: u64)
+                            -> Vec<(&'static str, Box<dyn Solver>)> {
+        vec![
+            ("simulated annealing", Box::new(SimulatedAnnealing {
+                neighborhood: neighborhood.clone(),
+                seed: Some(seed),
+                ..SimulatedAnnealing::default()
+            })),
+            ("tabu search", Box::new(TabuSearch { neighborhood, seed: Some(seed), ..TabuSearch::default() })),
+        ]
+    }
+
+    #[test]
+    pub fn test_trivial() {
+        let mut solvers: Vec<(&str, Box<dyn Solver>)> = vec![
+            ("brute force", Box::new(BruteForce)),
+            ("hill climbing", Box::new(HillClimbing::default())),
+        ];
+        solvers.extend(randomized_solvers(0));
+        for (name, solver) in solvers {
+            assert!(solver.solve_one(0).unwrap().size() == 0, "{}", name);
+            assert!(solver.solve_one(1).unwrap().is_valid(), "{}", name);
+            assert!(solver.solve_one(2).unwrap_err() == SolverError::NoSolutionsExist, "{}", name);
+            assert!(solver.solve_one(3).unwrap_err() == SolverError::NoSolutionsExist, "{}", name);
+        }
+    }
+
+    #[test]
+    pub fn test_unsupported() {
+        assert!(HillClimbing::default().all_solutions(8).unwrap_err() == SolverError::Unsupported);
+        assert!(HillClimbing::default().count_solutions(8).unwrap_err() == SolverError::Unsupported);
+    }
+
+    #[test]
+    pub fn test_neighborhoods() {
+        let neighborhoods: Vec<Arc<dyn Neighborhood + Send + Sync>> = vec![
+            Arc::new(ColumnMove),
+            Arc::new(RowRotation { k: 3 }),
+            Arc::new(MinConflictMove),
+        ];
+        for neighborhood in neighborhoods {
+            for &size in &[8, 30] {
+                for (name, solver) in neighborhood_solvers(neighborhood.clone(), 1) {
+                    let solution = solver.solve_one(size);
+                    assert!(solution.as_ref().is_ok_and(|q| q.is_valid()), "{} with {:?} size {}: {:?}",
+                            name, neighborhood, size, solution);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_same_start() {
+        // with no moves allowed, the local searches end on the board they start from
+        let starts = vec![
+            ("simulated annealing", SimulatedAnnealing { max_steps: 0, ..SimulatedAnnealing::default() }
+                .run_with_rng(8, &mut seeded_rng(5)).board),
+            ("tabu search", TabuSearch { max_steps: 0, ..TabuSearch::default() }.run_with_rng(8, &mut seeded_rng(5)).board),
+        ];
+        let expected = NQueens::new_random_permutation_with_rng(8, &mut seeded_rng(5));
+        for (name, start) in starts {
+            assert!(start == expected, "{}: {:?}", name, start);
+        }
+    }
+
+    #[test]
+    pub fn test_compared_with_hill_climbing() {
+        // on the same seeds the randomized solvers escape the local minima that plain hill
+        // climbing gets stuck in
+        let names: Vec<&str> = randomized_solvers(0).into_iter().map(|(name, _)| name).collect();
+        let mut solved = vec![0; names.len()];
+        let mut climbed = 0;
+        for seed in 0..20 {
+            for (i, (_, solver)) in randomized_solvers(seed).into_iter().enumerate() {
+                if solver.solve_one(8).is_ok() {
+                    solved[i] += 1;
+                }
+            }
+            let climbing = HillClimbing { seed: Some(seed), ..HillClimbing::default() };
+            if climbing.solve_one(8).is_ok() {
+                climbed += 1;
+            }
+        }
+        for (name, &count) in names.iter().zip(&solved) {
+            assert!(count == 20, "{} solved {}, hill climbing solved {}", name, count, climbed);
+        }
+        assert!(climbed < 20, "hill climbing solved {}", climbed);
+    }
+}
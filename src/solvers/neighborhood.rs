@@ -0,0 +1,356 @@
+use std::fmt;
+
+use rand::Rng;
+
+use nqueens_move::Move;
+use nqueens_tracked::NQueensTracked;
+
+/// A way of generating the boards a local search can step to from the current one. A neighbor is
+/// described by the `Move`s which lead to it, each in a different column, so that solvers can
+/// score it by applying the moves to an `NQueensTracked` with `apply_moves` and taking them back
+/// with `undo_moves`, without building a new board.
+///
+/// `HillClimbing`, `SimulatedAnnealing` and `TabuSearch` take any neighborhood, shared behind an
+/// `Arc`, so the methods take trait objects rather than being generic. Neighborhoods only move
+/// queens which are on the board, so empty columns stay empty.
+pub trait Neighborhood: fmt::Debug {
+    /// Calls `visit` with the moves leading to each neighbor of the board, in an order which only
+    /// depends on the board.
+    fn for_each_neighbor(&self, board: &NQueensTracked, visit: &mut dyn FnMut(&[Move]));
+
+    /// Replaces the contents of `moves` with those leading to a neighbor of the board drawn at
+    /// random, or clears it if the board has no neighbors.
+    fn random_neighbor(&self, board: &NQueensTracked, rng: &mut dyn Rng, moves: &mut Vec<Move>);
+}
+
+/// Moves one queen to another row of its column, giving the same neighbors as
+/// `NQueens::successors_iter`. This is what hill climbing has always used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColumnMove;
+
+/// Exchanges the rows of the queens in two columns, giving the same neighbors as
+/// `NQueens::swap_successors_iter` on a full board. The neighbors of a permutation board are all
+/// permutation boards, so a search using only these never has two queens on the same row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColumnSwap;
+
+/// Rotates the rows of the queens in `k` columns, so that each takes the row of the one `shift`
+/// places after it in column order, wrapping around, for every shift from 1 to `k-1`. With `k` of
+/// 2 this is `ColumnSwap`. There are C(n,k)*(k-1) neighbors, so `k` should be small.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowRotation {
+    pub k: usize,
+}
+
+/// Moves a queen which is in conflict to a row of its column attacked by as few other queens as
+/// possible, the move made by the min-conflicts heuristic. Boards with no conflicts have no
+/// neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MinConflictMove;
+
+/// Returns the columns which have a queen in them.
+fn set_columns(board: &NQueensTracked) -> Vec<usize> {
+    (0..board.size()).filter(|&column| board.get_option(column).is_some()).collect()
+}
+
+/// Makes the moves in order.
+pub fn apply_moves(board: &mut NQueensTracked, moves: &[Move]) {
+    for &m in moves {
+        board.apply_move(m);
+    }
+}
+
+/// Takes back moves made with `apply_moves`, in reverse order.
+pub fn undo_moves(board: &mut NQueensTracked, moves: &[Move]) {
+    for &m in moves.iter().rev() {
+        board.undo_move(m);
+    }
+}
+
+impl Neighborhood for ColumnMove {
+    fn for_each_neighbor(&self, board: &NQueensTracked, visit: &mut dyn FnMut(&[Move])) {
+        for m in board.board().moves_iter() {
+            visit(&[m]);
+        }
+    }
+
+    fn random_neighbor(&self, board: &NQueensTracked, mut rng: &mut dyn Rng, moves: &mut Vec<Move>) {
+        // `Rng`'s generic methods need a sized receiver, which `&mut dyn Rng` is but `dyn Rng`
+        // isn't
+        let rng = &mut rng;
+        moves.clear();
+        let size = board.size();
+        if size < 2 {
+            return;
+        }
+
+        // boards being searched are usually full, so the columns with queens are only listed if
+        // the first column drawn is empty, which keeps this O(1) on full boards
+        let mut column = rng.gen_range(0, size);
+        if board.get_option(column).is_none() {
+            let set_columns = set_columns(board);
+            if set_columns.is_empty() {
+                return;
+            }
+            column = set_columns[rng.gen_range(0, set_columns.len())];
+        }
+        let from = board.get(column);
+        let mut to = rng.gen_range(0, size-1);
+        if to >= from {
+            to += 1;
+        }
+        moves.push(Move { column, from, to });
+    }
+}
+
+impl Neighborhood for ColumnSwap {
+    fn for_each_neighbor(&self, board: &NQueensTracked, visit: &mut dyn FnMut(&[Move])) {
+        RowRotation { k: 2 }.for_each_neighbor(board, visit);
+    }
+
+    fn random_neighbor(&self, board: &NQueensTracked, mut rng: &mut dyn Rng, moves: &mut Vec<Move>) {
+        let rng = &mut rng;
+        moves.clear();
+        let size = board.size();
+        if size < 2 {
+            return;
+        }
+
+        // try a single pair in O(1) first, which always works on a permutation board
+        let a = rng.gen_range(0, size);
+        let mut b = rng.gen_range(0, size-1);
+        if b >= a {
+            b += 1;
+        }
+        match (board.get_option(a), board.get_option(b)) {
+            (Some(row_a), Some(row_b)) if row_a != row_b => {
+                moves.push(Move { column: a, from: row_a, to: row_b });
+                moves.push(Move { column: b, from: row_b, to: row_a });
+            }
+            _ => RowRotation { k: 2 }.random_neighbor(board, &mut **rng, moves),
+        }
+    }
+}
+
+impl RowRotation {
+    /// Fills `moves` with those rotating the rows of the given columns by `shift` places, leaving
+    /// out columns whose row doesn't change.
+    fn rotation(board: &NQueensTracked, columns: &[usize], shift: usize, moves: &mut Vec<Move>) {
+        moves.clear();
+        for (i, &column) in columns.iter().enumerate() {
+            let from = board.get(column);
+            let to = board.get(columns[(i + shift) % columns.len()]);
+            if from != to {
+                moves.push(Move { column, from, to });
+            }
+        }
+    }
+}
+
+impl Neighborhood for RowRotation {
+    fn for_each_neighbor(&self, board: &NQueensTracked, visit: &mut dyn FnMut(&[Move])) {
+        assert!(self.k >= 2, "a rotation needs at least 2 columns, not {}", self.k);
+        let set_columns = set_columns(board);
+        if set_columns.len() < self.k {
+            return;
+        }
+
+        // step through the k-combinations of the columns in lexicographic order
+        let mut indices: Vec<usize> = (0..self.k).collect();
+        let mut columns = vec![0; self.k];
+        let mut moves = Vec::with_capacity(self.k);
+        loop {
+            for (column, &i) in columns.iter_mut().zip(&indices) {
+                *column = set_columns[i];
+            }
+            for shift in 1..self.k {
+                RowRotation::rotation(board, &columns, shift, &mut moves);
+                if !moves.is_empty() {
+                    visit(&moves);
+                }
+            }
+
+            // find the last index which can still be increased
+            let last = match (0..self.k).rev().find(|&j| indices[j] < set_columns.len() - self.k + j) {
+                Some(last) => last,
+                None => break,
+            };
+            indices[last] += 1;
+            for j in last+1..self.k {
+                indices[j] = indices[j-1] + 1;
+            }
+        }
+    }
+
+    fn random_neighbor(&self, board: &NQueensTracked, mut rng: &mut dyn Rng, moves: &mut Vec<Move>) {
+        assert!(self.k >= 2, "a rotation needs at least 2 columns, not {}", self.k);
+        let rng = &mut rng;
+        moves.clear();
+        let size = board.size();
+        // if every queen is on the same row all rotations leave the board as it is
+        let mut rows = board.board().iter().filter_map(|&row| row);
+        let first_row = rows.next();
+        if set_columns(board).len() < self.k || rows.all(|row| Some(row) == first_row) {
+            return;
+        }
+
+        // draw distinct columns with queens until the rotation changes the board, which for a
+        // permutation board is the first time
+        let mut columns = Vec::with_capacity(self.k);
+        while moves.is_empty() {
+            columns.clear();
+            while columns.len() < self.k {
+                let column = rng.gen_range(0, size);
+                if board.get_option(column).is_some() && !columns.contains(&column) {
+                    columns.push(column);
+                }
+            }
+            columns.sort();
+            let shift = rng.gen_range(1, self.k);
+            RowRotation::rotation(board, &columns, shift, moves);
+        }
+    }
+}
+
+impl MinConflictMove {
+    /// Returns the fewest attackers on any row of the column other than the one its queen is on.
+    fn fewest_attackers(board: &NQueensTracked, column: usize) -> u32 {
+        let from = board.get(column);
+        (0..board.size()).filter(|&row| row != from).map(|row| board.attackers(column, row)).min().unwrap()
+    }
+}
+
+impl Neighborhood for MinConflictMove {
+    fn for_each_neighbor(&self, board: &NQueensTracked, visit: &mut dyn FnMut(&[Move])) {
+        if board.size() < 2 {
+            return;
+        }
+
+        for column in (0..board.size()).filter(|&column| board.is_attacked(column)) {
+            let from = board.get(column);
+            let fewest = MinConflictMove::fewest_attackers(board, column);
+            for to in (0..board.size()).filter(|&row| row != from && board.attackers(column, row) == fewest) {
+                visit(&[Move { column, from, to }]);
+            }
+        }
+    }
+
+    fn random_neighbor(&self, board: &NQueensTracked, mut rng: &mut dyn Rng, moves: &mut Vec<Move>) {
+        let rng = &mut rng;
+        moves.clear();
+        let attacked: Vec<usize> = (0..board.size()).filter(|&column| board.is_attacked(column)).collect();
+        if board.size() < 2 || attacked.is_empty() {
+            return;
+        }
+
+        let column = attacked[rng.gen_range(0, attacked.len())];
+        let from = board.get(column);
+        let fewest = MinConflictMove::fewest_attackers(board, column);
+        let rows: Vec<usize> = (0..board.size()).filter(|&row| row != from && board.attackers(column, row) == fewest).collect();
+        let to = rows[rng.gen_range(0, rows.len())];
+        moves.push(Move { column, from, to });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::{apply_moves, undo_moves, ColumnMove, ColumnSwap, MinConflictMove, Neighborhood, RowRotation};
+    use {Move, NQueens, NQueensTracked};
+    use solvers::seeded_rng;
+
+    /// Returns the boards the neighborhood leads to from the board.
+    fn neighbors(neighborhood: &dyn Neighborhood, board: &NQueens) -> Vec<NQueens> {
+        let tracked = NQueensTracked::new(board.clone());
+        let mut scratch = tracked.clone();
+        let mut boards = Vec::new();
+        neighborhood.for_each_neighbor(&tracked, &mut |moves: &[Move]| {
+            apply_moves(&mut scratch, moves);
+            assert!(scratch.count_conflicts() == scratch.board().count_conflicts());
+            boards.push(scratch.board().clone());
+            undo_moves(&mut scratch, moves);
+            assert!(scratch.board() == board);
+        });
+        boards
+    }
+
+    #[test]
+    pub fn test_column_move() {
+        let q = NQueens::new_random_with_rng(8, &mut seeded_rng(0));
+        let successors: Vec<NQueens> = q.successors_iter().collect();
+        assert!(neighbors(&ColumnMove, &q) == successors);
+    }
+
+    #[test]
+    pub fn test_column_swap() {
+        let q = NQueens::new_random_permutation_with_rng(8, &mut seeded_rng(0));
+        let successors: Vec<NQueens> = q.swap_successors_iter().collect();
+        assert!(neighbors(&ColumnSwap, &q) == successors);
+        assert!(neighbors(&RowRotation { k: 2 }, &q) == successors);
+    }
+
+    #[test]
+    pub fn test_row_rotation() {
+        let q = NQueens::from([0,1,2,3]);
+        let rotated = neighbors(&RowRotation { k: 3 }, &q);
+        // 4 ways to choose 3 columns, each rotated 2 ways
+        assert!(rotated.len() == 8, "{:?}", rotated);
+        assert!(rotated[0] == NQueens::from([1,2,0,3]));
+        assert!(rotated[1] == NQueens::from([2,0,1,3]));
+        assert!(rotated.iter().collect::<HashSet<_>>().len() == 8);
+
+        assert!(neighbors(&RowRotation { k: 4 }, &q).len() == 3);
+        assert!(neighbors(&RowRotation { k: 5 }, &q).is_empty());
+
+        // rotating queens on the same row doesn't change the board
+        assert!(neighbors(&RowRotation { k: 3 }, &NQueens::from([2,2,2])).is_empty());
+    }
+
+    #[test]
+    pub fn test_min_conflict_move() {
+        // every queen is attacked, and each moves to the least attacked of its other rows
+        let q = NQueens::from([0,1,3,3]);
+        let moved = neighbors(&MinConflictMove, &q);
+        let tracked = NQueensTracked::new(q.clone());
+        for board in &moved {
+            let column = (0..4).find(|&column| board.get(column) != q.get(column)).unwrap();
+            let fewest = (0..4).filter(|&row| row != q.get(column)).map(|row| tracked.attackers(column, row)).min().unwrap();
+            assert!(tracked.attackers(column, board.get(column)) == fewest, "{:?}", board);
+        }
+        assert!(!moved.is_empty());
+
+        assert!(neighbors(&MinConflictMove, &NQueens::from([1,3,0,2])).is_empty());
+    }
+
+    #[test]
+    pub fn test_random_neighbors() {
+        let neighborhoods: Vec<Box<dyn Neighborhood>> = vec![
+            Box::new(ColumnMove),
+            Box::new(ColumnSwap),
+            Box::new(RowRotation { k: 3 }),
+            Box::new(MinConflictMove),
+        ];
+        let mut rng = seeded_rng(1);
+        for neighborhood in &neighborhoods {
+            let q = NQueens::new_random_permutation_with_rng(6, &mut rng);
+            let all: HashSet<NQueens> = neighbors(&**neighborhood, &q).into_iter().collect();
+            let mut seen = HashSet::new();
+            let mut tracked = NQueensTracked::new(q.clone());
+            let mut moves = Vec::new();
+            for _ in 0..2000 {
+                neighborhood.random_neighbor(&tracked, &mut rng, &mut moves);
+                apply_moves(&mut tracked, &moves);
+                seen.insert(tracked.board().clone());
+                undo_moves(&mut tracked, &moves);
+            }
+            assert!(seen == all, "{:?} drew {} of {} neighbors", neighborhood, seen.len(), all.len());
+
+            // a board with no queens has no neighbors
+            let empty = NQueensTracked::new_empty(6);
+            neighborhood.random_neighbor(&empty, &mut rng, &mut moves);
+            assert!(moves.is_empty(), "{:?}", neighborhood);
+            assert!(neighbors(&**neighborhood, &NQueens::new_empty(6)).is_empty());
+        }
+    }
+}
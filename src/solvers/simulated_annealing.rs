@@ -0,0 +1,261 @@
+use std::fmt;
+use std::sync::Arc;
+
+use rand;
+use rand::Rng;
+
+use nqueens_struct::NQueens;
+use nqueens_tracked::NQueensTracked;
+use solvers::{seeded_rng, Solver, SolverError};
+use solvers::neighborhood::{apply_moves, undo_moves, ColumnSwap, Neighborhood};
+
+/// How the temperature of `SimulatedAnnealing` falls as the search goes on. The temperature at
+/// step `t` is given by `Schedule::temperature(t)`.
+#[derive(Clone)]
+pub enum Schedule {
+    /// `initial * alpha^t`, for `alpha` a little below 1.
+    Exponential { initial: f64, alpha: f64 },
+    /// Falls in a straight line from `initial` to 0 at step `steps`, and stays at 0 after that.
+    Linear { initial: f64, steps: usize },
+    /// `initial * ln(2) / ln(t+2)`, the classic schedule which falls slowly enough to converge in
+    /// theory but is usually too slow in practice.
+    Logarithmic { initial: f64 },
+    /// Any function of the step number.
+    Custom(Arc<dyn Fn(usize) -> f64 + Send + Sync>),
+}
+
+impl Schedule {
+    /// Returns the temperature at the given step. At a temperature of 0 or less only moves which
+    /// don't add conflicts are accepted.
+    pub fn temperature(&self, step: usize) -> f64 {
+        match *self {
+            Schedule::Exponential { initial, alpha } => initial*alpha.powf(step as f64),
+            Schedule::Linear { initial, steps } => {
+                if step >= steps {
+                    return 0.0;
+                }
+                initial*(1.0 - step as f64/steps as f64)
+            }
+            Schedule::Logarithmic { initial } => initial*2f64.ln()/(step as f64 + 2.0).ln(),
+            Schedule::Custom(ref f) => f(step),
+        }
+    }
+}
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Schedule::Exponential { initial, alpha } => {
+                write!(f, "Exponential {{ initial: {:?}, alpha: {:?} }}", initial, alpha)
+            }
+            Schedule::Linear { initial, steps } => {
+                write!(f, "Linear {{ initial: {:?}, steps: {:?} }}", initial, steps)
+            }
+            Schedule::Logarithmic { initial } => write!(f, "Logarithmic {{ initial: {:?} }}", initial),
+            Schedule::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// A `Solver` which runs simulated annealing from a random permutation board, moving to random
+/// neighbors of the current board. A move which adds `d` conflicts is accepted with probability
+/// `exp(-d/T)` at temperature `T`, and moves which don't add conflicts are always accepted. See
+/// `SimulatedAnnealing::run`.
+#[derive(Debug, Clone)]
+pub struct SimulatedAnnealing {
+    pub schedule: Schedule,
+    /// The boards a move can go to from the current one. The default is `ColumnSwap`, which swaps
+    /// the rows of two columns so that no two queens ever share a row.
+    pub neighborhood: Arc<dyn Neighborhood + Send + Sync>,
+    /// The number of moves to try before giving up.
+    pub max_steps: usize,
+    /// Seed for the starting board and the moves, so that runs can be reproduced. If None, they
+    /// are drawn from `rand::thread_rng()` every time.
+    pub seed: Option<u64>,
+}
+
+impl Default for SimulatedAnnealing {
+    fn default() -> SimulatedAnnealing {
+        SimulatedAnnealing {
+            schedule: Schedule::Exponential { initial: 2.0, alpha: 0.9995 },
+            neighborhood: Arc::new(ColumnSwap),
+            max_steps: 100_000,
+            seed: None,
+        }
+    }
+}
+
+/// The board that a run of `SimulatedAnnealing` ended with, which is a solution if `conflicts` is
+/// 0, along with how many moves of each kind were tried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnnealingRun {
+    pub board: NQueens,
+    pub conflicts: u32,
+    /// The number of moves tried, which is the sum of the four counts below.
+    pub steps: usize,
+    /// Moves which removed conflicts.
+    pub accepted_better: usize,
+    /// Moves which left the number of conflicts the same.
+    pub accepted_equal: usize,
+    /// Moves which added conflicts, but were accepted anyway.
+    pub accepted_worse: usize,
+    /// Moves which would have added conflicts and were undone.
+    pub rejected: usize,
+}
+
+impl AnnealingRun {
+    /// The fraction of the moves tried which were accepted.
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.steps == 0 {
+            return 0.0;
+        }
+        (self.steps - self.rejected) as f64/self.steps as f64
+    }
+}
+
+impl Solver for SimulatedAnnealing {
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        if size == 2 || size == 3 {
+            return Err(SolverError::NoSolutionsExist);
+        }
+
+        let run = self.run(size);
+        if run.conflicts != 0 {
+            return Err(SolverError::BudgetExhausted);
+        }
+        Ok(run.board)
+    }
+}
+
+impl SimulatedAnnealing {
+    /// Anneals a random permutation board of the given size until it has no conflicts or
+    /// `max_steps` moves have been tried, using the seed if there is one.
+    pub fn run(&self, size: usize) -> AnnealingRun {
+        match self.seed {
+            Some(seed) => self.run_with_rng(size, &mut seeded_rng(seed)),
+            None => self.run_with_rng(size, &mut rand::thread_rng()),
+        }
+    }
+
+    /// Like `run`, but draws the starting board and the moves from the given random number
+    /// generator instead of the seed. The starting board is drawn first, with
+    /// `NQueens::new_random_permutation_with_rng`.
+    pub fn run_with_rng<R: Rng>(&self, size: usize, rng: &mut R) -> AnnealingRun {
+        let mut board = NQueensTracked::new(NQueens::new_random_permutation_with_rng(size, rng));
+        let mut moves = Vec::new();
+        let mut run = AnnealingRun {
+            board: NQueens::new_empty(0),
+            conflicts: 0,
+            steps: 0,
+            accepted_better: 0,
+            accepted_equal: 0,
+            accepted_worse: 0,
+            rejected: 0,
+        };
+
+        while board.count_conflicts() != 0 && run.steps < self.max_steps {
+            self.neighborhood.random_neighbor(&board, rng, &mut moves);
+            if moves.is_empty() {
+                break;
+            }
+            let temperature = self.schedule.temperature(run.steps);
+            run.steps += 1;
+
+            let before = board.count_conflicts();
+            apply_moves(&mut board, &moves);
+            let after = board.count_conflicts();
+
+            if after < before {
+                run.accepted_better += 1;
+            }
+            else if after == before {
+                run.accepted_equal += 1;
+            }
+            else if temperature > 0.0 && rng.gen::<f64>() < (-((after-before) as f64)/temperature).exp() {
+                run.accepted_worse += 1;
+            }
+            else {
+                undo_moves(&mut board, &moves);
+                run.rejected += 1;
+            }
+        }
+
+        run.conflicts = board.count_conflicts();
+        run.board = board.into_inner();
+        run
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{Schedule, SimulatedAnnealing};
+    use solvers::{Solver, SolverError};
+
+    #[test]
+    pub fn test_schedules() {
+        let exponential = Schedule::Exponential { initial: 2.0, alpha: 0.5 };
+        assert!(exponential.temperature(0) == 2.0);
+        assert!(exponential.temperature(3) == 0.25);
+
+        let linear = Schedule::Linear { initial: 2.0, steps: 4 };
+        assert!(linear.temperature(0) == 2.0);
+        assert!(linear.temperature(1) == 1.5);
+        assert!(linear.temperature(4) == 0.0);
+        assert!(linear.temperature(100) == 0.0);
+
+        let logarithmic = Schedule::Logarithmic { initial: 2.0 };
+        assert!(logarithmic.temperature(0) == 2.0);
+        assert!((logarithmic.temperature(2) - 1.0).abs() < 1e-12);
+
+        let custom = Schedule::Custom(Arc::new(|step| 1.0/(step as f64 + 1.0)));
+        assert!(custom.temperature(3) == 0.25);
+        assert!(format!("{:?}", custom) == "Custom(..)");
+    }
+
+    #[test]
+    pub fn test_solutions() {
+        let schedules = vec![
+            Schedule::Exponential { initial: 2.0, alpha: 0.999 },
+            Schedule::Linear { initial: 2.0, steps: 20_000 },
+            Schedule::Logarithmic { initial: 0.5 },
+            Schedule::Custom(Arc::new(|step| if step < 1000 { 1.0 } else { 0.1 })),
+        ];
+        for schedule in schedules {
+            for &size in &[8, 30] {
+                let annealing = SimulatedAnnealing { schedule: schedule.clone(), seed: Some(1), ..SimulatedAnnealing::default() };
+                let run = annealing.run(size);
+                assert!(run.conflicts == 0 && run.board.is_valid(), "{:?} size {}: {:?}", schedule, size, run);
+                assert!(annealing.run(size) == run, "{:?}", schedule);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_statistics() {
+        let annealing = SimulatedAnnealing { seed: Some(2), ..SimulatedAnnealing::default() };
+        let run = annealing.run(50);
+        assert!(run.steps == run.accepted_better + run.accepted_equal + run.accepted_worse + run.rejected);
+        assert!(run.accepted_better > 0 && run.accepted_worse > 0 && run.rejected > 0, "{:?}", run);
+        assert!(run.acceptance_rate() > 0.0 && run.acceptance_rate() < 1.0);
+
+        // at zero temperature nothing which adds conflicts is accepted
+        let cold = SimulatedAnnealing {
+            schedule: Schedule::Linear { initial: 0.0, steps: 0 },
+            max_steps: 1000,
+            seed: Some(2),
+            ..SimulatedAnnealing::default()
+        };
+        let run = cold.run(50);
+        assert!(run.accepted_worse == 0, "{:?}", run);
+    }
+
+    #[test]
+    pub fn test_budget_exhausted() {
+        let annealing = SimulatedAnnealing { max_steps: 0, seed: Some(0), ..SimulatedAnnealing::default() };
+        assert!(annealing.solve_one(30).unwrap_err() == SolverError::BudgetExhausted);
+        assert!(annealing.run(30).steps == 0);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rand;
+use rand::Rng;
+
+use nqueens_move::Move;
+use nqueens_struct::NQueens;
+use nqueens_tracked::NQueensTracked;
+use solvers::{seeded_rng, Solver, SolverError};
+use solvers::neighborhood::{apply_moves, undo_moves, ColumnSwap, Neighborhood};
+
+/// A `Solver` which runs tabu search from a random permutation board. Every step moves to the
+/// best neighbor that isn't tabu, even if it has more conflicts, so unlike `HillClimbing` it
+/// doesn't stop at the first local minimum; a queen can't move back to a row its column left for
+/// `tenure` steps, so that the search doesn't immediately undo its moves. See `TabuSearch::run`.
+///
+/// The tabu list holds (column, row) pairs rather than the swaps themselves. That forbids undoing
+/// a swap just the same, but also works for neighborhoods other than `ColumnSwap`, whose moves
+/// aren't swaps.
+#[derive(Debug, Clone)]
+pub struct TabuSearch {
+    /// The number of steps after a queen leaves a row during which it can't move back to it.
+    pub tenure: usize,
+    /// The number of moves to make before giving up.
+    pub max_steps: usize,
+    /// Allow a tabu move anyway if it would leave fewer conflicts than any board seen so far.
+    pub aspiration: bool,
+    /// Seed for the starting board and for breaking ties between moves, so that runs can be
+    /// reproduced. If None, they are drawn from `rand::thread_rng()` every time.
+    pub seed: Option<u64>,
+    /// The boards a move can go to from the current one. The default is `ColumnSwap`, which swaps
+    /// the rows of two columns so that no two queens ever share a row.
+    pub neighborhood: Arc<dyn Neighborhood + Send + Sync>,
+}
+
+impl Default for TabuSearch {
+    fn default() -> TabuSearch {
+        TabuSearch {
+            tenure: 10,
+            max_steps: 10_000,
+            aspiration: true,
+            seed: None,
+            neighborhood: Arc::new(ColumnSwap),
+        }
+    }
+}
+
+/// The board with the fewest conflicts found by a run of `TabuSearch`, which is a solution if
+/// `conflicts` is 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TabuRun {
+    pub board: NQueens,
+    pub conflicts: u32,
+    /// The number of moves made.
+    pub steps: usize,
+    /// The number of those moves which were tabu but allowed by the aspiration criterion.
+    pub aspiration_steps: usize,
+}
+
+impl Solver for TabuSearch {
+    fn solve_one(&self, size: usize) -> Result<NQueens, SolverError> {
+        if size == 2 || size == 3 {
+            return Err(SolverError::NoSolutionsExist);
+        }
+
+        let run = self.run(size);
+        if run.conflicts != 0 {
+            return Err(SolverError::BudgetExhausted);
+        }
+        Ok(run.board)
+    }
+}
+
+impl TabuSearch {
+    /// Searches from a random permutation board of the given size until it finds one with no
+    /// conflicts or has made `max_steps` moves, using the seed if there is one.
+    pub fn run(&self, size: usize) -> TabuRun {
+        match self.seed {
+            Some(seed) => self.run_with_rng(size, &mut seeded_rng(seed)),
+            None => self.run_with_rng(size, &mut rand::thread_rng()),
+        }
+    }
+
+    /// Like `run`, but draws the starting board and breaks ties with the given random number
+    /// generator. The starting board is drawn first, with
+    /// `NQueens::new_random_permutation_with_rng`.
+    ///
+    /// Each step tries every neighbor, counting the conflicts it would leave with
+    /// `NQueensTracked`, and moves to the one leaving the fewest which is allowed, breaking ties
+    /// randomly. If every neighbor is tabu, the best of them is moved to anyway.
+    pub fn run_with_rng<R: Rng>(&self, size: usize, rng: &mut R) -> TabuRun {
+        let mut board = NQueensTracked::new(NQueens::new_random_permutation_with_rng(size, rng));
+        let mut best = board.board().clone();
+        let mut best_conflicts = board.count_conflicts();
+        // the step until which each queen can't move back to a row, keyed by (column, row)
+        let mut tabu_until: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut steps = 0;
+        let mut aspiration_steps = 0;
+
+        while best_conflicts != 0 && steps < self.max_steps {
+            let is_tabu = |moves: &[Move]| {
+                moves.iter().any(|m| tabu_until.get(&(m.column, m.to)).is_some_and(|&until| steps < until))
+            };
+
+            // the best allowed neighbor and the best of all neighbors, as (conflicts, ties seen,
+            // moves)
+            let mut best_allowed: Option<(u32, usize, Vec<Move>)> = None;
+            let mut best_any: Option<(u32, usize, Vec<Move>)> = None;
+            let mut scratch = board.clone();
+            self.neighborhood.for_each_neighbor(&board, &mut |moves: &[Move]| {
+                apply_moves(&mut scratch, moves);
+                let conflicts = scratch.count_conflicts();
+                undo_moves(&mut scratch, moves);
+
+                let aspirates = self.aspiration && conflicts < best_conflicts;
+                if aspirates || !is_tabu(moves) {
+                    consider(&mut best_allowed, conflicts, moves, rng);
+                }
+                consider(&mut best_any, conflicts, moves, rng);
+            });
+
+            let (conflicts, _, moves) = match best_allowed.or(best_any) {
+                Some(candidate) => candidate,
+                None => break,
+            };
+            if is_tabu(&moves) && self.aspiration && conflicts < best_conflicts {
+                aspiration_steps += 1;
+            }
+            apply_moves(&mut board, &moves);
+            steps += 1;
+            for m in &moves {
+                tabu_until.insert((m.column, m.from), steps + self.tenure);
+            }
+
+            if conflicts < best_conflicts {
+                best = board.board().clone();
+                best_conflicts = conflicts;
+            }
+        }
+
+        TabuRun {
+            board: best,
+            conflicts: best_conflicts,
+            steps,
+            aspiration_steps,
+        }
+    }
+}
+
+/// Replaces `best` with the neighbor if it leaves fewer conflicts, or with probability 1/k if it's
+/// the k-th neighbor found leaving the same number, so that ties are broken uniformly at random.
+fn consider<R: Rng>(best: &mut Option<(u32, usize, Vec<Move>)>, conflicts: u32, moves: &[Move], rng: &mut R) {
+    match *best {
+        Some((best_conflicts, _, _)) if conflicts > best_conflicts => {}
+        Some((best_conflicts, ref mut ties, ref mut best_moves)) if conflicts == best_conflicts => {
+            *ties += 1;
+            if rng.gen_range(0, *ties) == 0 {
+                *best_moves = moves.to_vec();
+            }
+        }
+        _ => *best = Some((conflicts, 1, moves.to_vec())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TabuSearch;
+    use solvers::{Solver, SolverError};
+
+    #[test]
+    pub fn test_solutions() {
+        for &size in &[4, 8, 30] {
+            for seed in 0..5 {
+                let tabu = TabuSearch { seed: Some(seed), ..TabuSearch::default() };
+                let run = tabu.run(size);
+                assert!(run.conflicts == 0 && run.board.is_valid(), "size {} seed {}: {:?}", size, seed, run);
+                assert!(tabu.run(size) == run);
+
+                // the queens are only ever swapped, so every row is still used once
+                let mut rows: Vec<usize> = run.board.iter().map(|row| row.unwrap()).collect();
+                rows.sort();
+                assert!(rows == (0..size).collect::<Vec<usize>>());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_tenure() {
+        // with no tenure nothing is ever tabu, so aspiration never comes into it
+        for seed in 0..20 {
+            let run = TabuSearch { tenure: 0, seed: Some(seed), ..TabuSearch::default() }.run(8);
+            assert!(run.aspiration_steps == 0, "{:?}", run);
+        }
+
+        // with a tenure longer than the run every swap eventually puts a queen back on a row it
+        // has left, which must not stop the search
+        let run = TabuSearch { tenure: 1000, max_steps: 100, aspiration: false, seed: Some(0), ..TabuSearch::default() }.run(8);
+        assert!(run.steps == 100 || run.conflicts == 0, "{:?}", run);
+    }
+
+    #[test]
+    pub fn test_aspiration() {
+        let mut aspiration_steps = 0;
+        for seed in 0..20 {
+            let run = TabuSearch { seed: Some(seed), ..TabuSearch::default() }.run(30);
+            assert!(run.conflicts == 0);
+            aspiration_steps += run.aspiration_steps;
+
+            let run = TabuSearch { aspiration: false, seed: Some(seed), ..TabuSearch::default() }.run(30);
+            assert!(run.aspiration_steps == 0);
+        }
+        assert!(aspiration_steps > 0);
+    }
+
+    #[test]
+    pub fn test_budget_exhausted() {
+        let tabu = TabuSearch { max_steps: 1, seed: Some(0), ..TabuSearch::default() };
+        assert!(tabu.solve_one(30).unwrap_err() == SolverError::BudgetExhausted);
+        assert!(tabu.run(30).steps == 1);
+    }
+}